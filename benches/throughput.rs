@@ -0,0 +1,66 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nu_plugin_cer::certificate::{get_pem_values, RecordOptions};
+use nu_protocol::Span;
+
+// baselines for performance work, run with cargo bench and compare with --save-baseline
+const ROOTS: &str = include_str!("fixtures/mozilla-roots.pem");
+
+fn get_certificates() -> Vec<&'static str> {
+    ROOTS
+        .match_indices("-----BEGIN CERTIFICATE-----")
+        .map(|(start, _begin)| {
+            let end = start
+                + ROOTS[start..]
+                    .find("-----END CERTIFICATE-----")
+                    .unwrap_or(0);
+            &ROOTS[start..end + "-----END CERTIFICATE-----\n".len()]
+        })
+        .collect()
+}
+
+fn parse(criterion: &mut Criterion) {
+    let certificates = get_certificates();
+    // the store has fewer certificates than a large bundle, so the bundle repeats them
+    let bundles = [
+        ("single certificate", certificates[..1].concat()),
+        (
+            "150 certificate bundle",
+            certificates.iter().cycle().take(150).copied().collect(),
+        ),
+        ("mozilla root store", ROOTS.to_string()),
+    ];
+    // a few roots have email SANs, which are only read as san_other
+    let options = RecordOptions {
+        san_types: Some(vec!["dns".into(), "ip".into()]),
+        ..Default::default()
+    };
+    let span = Span::test_data();
+    let mut group = criterion.benchmark_group("pem");
+    for (name, pem) in &bundles {
+        let count = get_pem_values(pem, &options, span).expect("records").len();
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), pem, |bencher, pem| {
+            bencher.iter(|| get_pem_values(pem, &options, span))
+        });
+    }
+    group.finish();
+}
+
+// pfx files are only imported on windows, other platforms return an error at once
+#[cfg(all(windows, feature = "pkcs12"))]
+fn import(criterion: &mut Criterion) {
+    use nu_plugin_cer::pfx::get_pfx_values;
+
+    const PFX: &[u8] = include_bytes!("../src/tests/fixtures/with-key.pfx");
+    let options = RecordOptions::default();
+    let span = Span::test_data();
+    criterion.bench_function("pfx import", |bencher| {
+        bencher.iter(|| get_pfx_values(PFX, Some("fixture"), true, &options, span))
+    });
+}
+
+#[cfg(not(all(windows, feature = "pkcs12")))]
+fn import(_criterion: &mut Criterion) {}
+
+criterion_group!(benches, parse, import);
+criterion_main!(benches);
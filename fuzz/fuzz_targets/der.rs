@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nu_plugin_cer::certificate::{get_record, RecordOptions};
+use nu_protocol::Span;
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+// the DER certificates of `cer lint`, `cer spiffe` and the other binary inputs
+fuzz_target!(|data: &[u8]| {
+    let Ok((_rem, cer)) = X509Certificate::from_der(data) else {
+        return;
+    };
+    let options = RecordOptions {
+        rdns: true,
+        key_details: true,
+        full_sans: true,
+        ..Default::default()
+    };
+    let _ = get_record(&cer, &options, Span::unknown());
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nu_plugin_cer::certificate::{get_pem_values, RecordOptions};
+use nu_protocol::Span;
+
+// `open bundle.pem | cer`, with every optional column
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data).into_owned();
+    let options = RecordOptions {
+        rdns: true,
+        key_details: true,
+        full_sans: true,
+        ..Default::default()
+    };
+    let _ = get_pem_values(&text, &options, Span::unknown());
+});
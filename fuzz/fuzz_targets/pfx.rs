@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nu_plugin_cer::{certificate::RecordOptions, pfx::get_pfx_values};
+use nu_protocol::Span;
+
+// `open --raw store.pfx | cer`, only imported on windows with the pkcs12 feature
+fuzz_target!(|data: &[u8]| {
+    let options = RecordOptions::default();
+    let _ = get_pfx_values(data, None, true, &options, Span::unknown());
+});
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use nu_protocol::{LabeledError, Value};
+
+/// Translations of help texts and lint findings, keyed by their English template.
+/// Column names are never translated, so scripts keep working in every locale.
+#[derive(Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Reads the catalog of the selected locale, like
+    /// $env.config.plugins.cer.locale = de and
+    /// $env.config.plugins.cer.catalogs = {de: {'{0} without digitalSignature': '{0} ohne digitalSignature'}}
+    pub fn from_config(config: Option<&Value>) -> Catalog {
+        let Some(config) = config else {
+            return Catalog::default();
+        };
+        let catalog = config
+            .get_data_by_key("locale")
+            .and_then(|locale| locale.into_string().ok())
+            .and_then(|locale| config.get_data_by_key("catalogs")?.get_data_by_key(&locale))
+            .and_then(|catalog| catalog.into_record().ok());
+        let messages = catalog
+            .into_iter()
+            .flatten()
+            .filter_map(|(template, text)| Some((template, text.into_string().ok()?)))
+            .collect();
+        Catalog { messages }
+    }
+
+    /// Translates a template and fills its {0}, {1}... placeholders
+    pub fn text(&self, template: &str, args: &[&str]) -> String {
+        let mut rest = self.messages.get(template).map_or(template, String::as_str);
+        // a single scan, so placeholders inside the arguments are kept as they are
+        let mut text = String::with_capacity(rest.len());
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let arg = rest.find('}').and_then(|end| {
+                let arg = args.get(rest[1..end].parse::<usize>().ok()?)?;
+                Some((arg, end))
+            });
+            match arg {
+                Some((arg, end)) => {
+                    text.push_str(arg);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    /// Translates the help of an error line by line, lines without a translation are kept
+    pub fn localize(&self, mut error: LabeledError) -> LabeledError {
+        if self.messages.is_empty() {
+            return error;
+        }
+        error.help = error.help.map(|help| {
+            help.lines()
+                .map(|line| self.text(line, &[]))
+                .collect::<Vec<String>>()
+                .join("\n")
+        });
+        error
+    }
+}
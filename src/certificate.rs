@@ -1,74 +1,419 @@
-use chrono::DateTime;
+use std::{net::IpAddr, str::Utf8Error};
+
+use chrono::{DateTime, Utc};
 use data_encoding::HEXLOWER;
 use nu_protocol::{Record, Span, Value};
-use schannel::{
-    cert_context::{CertContext, HashAlgorithm},
-    cert_store::PfxImportOptions,
-};
+use sha2::{Digest, Sha256};
 use x509_parser::{
-    certificate::X509Certificate, error::X509Error, extensions::GeneralName, pem::Pem,
-    prelude::FromDer, x509::X509Name,
+    certificate::X509Certificate,
+    certification_request::X509CertificationRequest,
+    cri_attributes::ParsedCriAttribute,
+    der_parser::asn1_rs::{Any, Oid},
+    error::X509Error,
+    extensions::{ExtendedKeyUsage, GeneralName, KeyUsage, ParsedExtension, X509Extension},
+    pem::Pem,
+    prelude::FromDer,
+    x509::{AttributeTypeAndValue, X509Name},
 };
 
-use crate::error::CerError;
-
-type CerResult<T> = Result<T, CerError>;
-
-pub fn get_pfx_values(data: &[u8], password: Option<Value>, span: Span) -> CerResult<Vec<Value>> {
-    let mut pfx = PfxImportOptions::new();
-    pfx.no_persist_key(true);
-    pfx.include_extended_properties(true);
-    if let Some(password) = password {
-        let password = password.as_str().map_err(CerError::Password)?;
-        pfx.password(password);
-    }
-    let store = pfx.import(data).map_err(CerError::Pfx)?;
-    let values = store
-        .certs()
-        .map(|cer| {
-            let der = cer.to_der();
-            let (_rem, pem) =
-                x509_parser::certificate::X509Certificate::from_der(der).map_err(CerError::Der)?;
-            let mut record = get_record(&pem, span)?;
-            record.push(
-                "friendly",
-                Value::string(get_pfx_friendly_name(&cer)?, span),
-            );
-            record.push("thumbprint", Value::string(get_pfx_thumbprint(&cer)?, span));
-            let value = Value::record(record, span);
-            Ok(value)
-        })
-        .collect::<Result<Vec<Value>, CerError>>()?;
-    Ok(values)
+use crate::{
+    core::{
+        dn::decode_wide_string,
+        key::{
+            get_hash_bits, get_key_algorithm, get_key_size, get_security_bits, get_signature_hash,
+        },
+        limits::{check_certificate, check_limit, Limits},
+        oid::get_oid_name,
+    },
+    error::CerError,
+};
+
+pub type CerResult<T> = Result<T, CerError>;
+
+/// SANs listed per column without full_sans, so a CDN certificate does not dominate a table
+pub const SAN_PREVIEW: usize = 100;
+
+/// Optional columns of the certificate records, and the limits on the input
+#[derive(Default)]
+pub struct RecordOptions {
+    pub rdns: bool,
+    /// kinds of SAN in the san column, like dns and ip, the others go to san_other
+    pub san_types: Option<Vec<String>>,
+    /// time of the analysis, shared by all records of a call
+    pub analyzed_at: Option<DateTime<Utc>>,
+    /// add the key algorithm, key size, signature hash and security bits
+    pub key_details: bool,
+    /// list every SAN instead of the first SAN_PREVIEW of each list
+    pub full_sans: bool,
+    pub limits: Limits,
+}
+
+/// Reads the limits from the plugin config, like $env.config.plugins.cer.limits = {max_sans: 100}
+pub fn get_limits(config: Option<Value>) -> Limits {
+    let mut limits = Limits::default();
+    let Some(config) = config.and_then(|config| config.get_data_by_key("limits")) else {
+        return limits;
+    };
+    let limit = |column: &str| {
+        config
+            .get_data_by_key(column)
+            .and_then(|limit| limit.as_int().ok())
+            .and_then(|limit| usize::try_from(limit).ok())
+    };
+    if let Some(max) = limit("max_certificates") {
+        limits.max_certificates = max;
+    }
+    if let Some(max) = limit("max_sans") {
+        limits.max_sans = max;
+    }
+    if let Some(max) = limit("max_extension_size") {
+        limits.max_extension_size = max;
+    }
+    if let Some(max) = limit("max_output_size") {
+        limits.max_output_size = max;
+    }
+    limits
+}
+
+/// Approximate memory used by a value, to stop before a huge list exhausts the plugin process
+pub fn get_value_size(value: &Value) -> usize {
+    let size = match value {
+        Value::String { val, .. } => val.len(),
+        Value::Binary { val, .. } => val.len(),
+        Value::List { vals, .. } => vals.iter().map(get_value_size).sum(),
+        Value::Record { val, .. } => val
+            .iter()
+            .map(|(column, value)| column.len() + get_value_size(value))
+            .sum(),
+        _ => 0,
+    };
+    std::mem::size_of::<Value>() + size
+}
+
+/// Whether a certificate record is self-issued, like a root certificate authority
+pub fn is_root(val: &Value) -> bool {
+    let subject = val.get_data_by_key("subject");
+    subject.is_some() && subject == val.get_data_by_key("ca_subject")
 }
 
-pub fn get_pem_values(val: &String, span: Span) -> CerResult<Vec<Value>> {
+pub enum PemKind {
+    Certificate,
+    Request,
+}
+
+pub fn get_pem_kind(pem: &Pem) -> PemKind {
+    match pem.label.as_str() {
+        // RFC 7468 section 7: "NEW CERTIFICATE REQUEST" is the legacy label, both are accepted
+        "CERTIFICATE REQUEST" | "NEW CERTIFICATE REQUEST" => PemKind::Request,
+        _ => PemKind::Certificate,
+    }
+}
+
+pub fn get_pem_values(val: &String, options: &RecordOptions, span: Span) -> CerResult<Vec<Value>> {
+    let mut output_size = 0;
     Pem::iter_from_buffer(val.as_bytes())
-        .map(|pem| {
+        .enumerate()
+        .map(|(index, pem)| {
+            check_limit("certificates", index + 1, options.limits.max_certificates)?;
             let pem = pem.map_err(CerError::Pem)?;
-            let cer = pem.parse_x509().map_err(CerError::Parse)?;
-            let mut record = get_record(&cer, span)?;
+            let mut record = match get_pem_kind(&pem) {
+                PemKind::Certificate => {
+                    let cer = pem.parse_x509().map_err(CerError::Parse)?;
+                    get_record(&cer, options, span)?
+                }
+                PemKind::Request => {
+                    let (_rem, csr) = X509CertificationRequest::from_der(&pem.contents)
+                        .map_err(CerError::Request)?;
+                    get_request_record(&csr, options, span)?
+                }
+            };
             record.push("thumbprint", get_thumbprint(&pem, span));
+            record.push("sha256", get_sha256(&pem.contents, span));
             let value = Value::record(record, span);
+            output_size += get_value_size(&value);
+            check_limit(
+                "bytes of output",
+                output_size,
+                options.limits.max_output_size,
+            )?;
             Ok(value)
         })
         .collect::<Result<Vec<Value>, CerError>>()
 }
 
-pub fn get_record(cer: &X509Certificate, span: Span) -> CerResult<Record> {
+pub fn get_record(cer: &X509Certificate, options: &RecordOptions, span: Span) -> CerResult<Record> {
+    check_certificate(cer, &options.limits)?;
     let mut record = Record::new();
     record.push("cn", get_common_names(cer, span)?);
     record.push("subject", get_subject(cer, span));
-    record.push("san", get_sans(cer, span)?);
+    let sans = cer.subject_alternative_name().map_err(CerError::San)?;
+    let names = sans.map_or(&[][..], |sans| sans.value.general_names.as_slice());
+    push_sans(&mut record, names, options, span)?;
     record.push("ca", get_ca_common_names(cer, span)?);
     record.push("ca_subject", get_ca_subject(cer, span));
     record.push("expiration", get_expiration(cer, span)?);
+    push_purpose_fields(&mut record, cer, names, span)?;
+    if options.key_details {
+        let algorithm = get_key_algorithm(cer.public_key());
+        let size = get_key_size(cer.public_key());
+        let hash = get_signature_hash(&cer.signature_algorithm);
+        // self-signed certificates are trusted by their key, their signature is not checked
+        let mut bits = get_security_bits(&algorithm, size);
+        if cer.subject() != cer.issuer() {
+            bits = bits.min(get_hash_bits(&hash));
+        }
+        record.push("key_algorithm", Value::string(algorithm, span));
+        record.push("key_size", Value::int(size as i64, span));
+        record.push("signature_hash", Value::string(hash, span));
+        record.push("security_bits", Value::int(bits as i64, span));
+    }
+    if options.rdns {
+        record.push("subject_rdns", parse_rdns(cer.subject(), span));
+        record.push("ca_subject_rdns", parse_rdns(cer.issuer(), span));
+    }
+    if let Some(analyzed_at) = options.analyzed_at {
+        record.push("analyzed_at", Value::date(analyzed_at.into(), span));
+    }
     Ok(record)
 }
 
+/// Columns only certificates of a purpose have, from their extended key usage
+fn push_purpose_fields(
+    record: &mut Record,
+    cer: &X509Certificate,
+    sans: &[GeneralName],
+    span: Span,
+) -> CerResult<()> {
+    let Some(eku) = cer.extended_key_usage().map_err(CerError::Extension)? else {
+        return Ok(());
+    };
+    if eku.value.email_protection {
+        record.push("email_addresses", get_email_addresses(cer, sans, span)?);
+    }
+    if eku.value.code_signing {
+        // Windows lifetime signing makes signatures expire with the certificate, even timestamped
+        let lifetime_signing = eku
+            .value
+            .other
+            .iter()
+            .any(|oid| oid.to_id_string() == "1.3.6.1.4.1.311.10.3.13");
+        record.push("signer_name", get_signer_name(cer, span)?);
+        record.push("timestamping_allowed", Value::bool(!lifetime_signing, span));
+        record.push("lifetime_signing", Value::bool(lifetime_signing, span));
+    }
+    if eku.value.client_auth {
+        let (kind, identity) = match get_client_identity(cer, sans)? {
+            Some((kind, identity)) => (Value::string(kind, span), Value::string(identity, span)),
+            None => (Value::nothing(span), Value::nothing(span)),
+        };
+        record.push("identity", identity);
+        record.push("identity_type", kind);
+    }
+    Ok(())
+}
+
+// Microsoft user principal name: otherName [0] EXPLICIT UTF8String
+fn parse_upn(oid: &Oid, value: &[u8]) -> Option<String> {
+    if oid.to_id_string() != "1.3.6.1.4.1.311.20.2.3" {
+        return None;
+    }
+    let (_rem, explicit) = Any::from_der(value).ok()?;
+    let (_rem, upn) = Any::from_der(explicit.data).ok()?;
+    std::str::from_utf8(upn.data).ok().map(str::to_string)
+}
+
+/// The name mTLS systems authenticate a client by: its SPIFFE ID, its UPN or its common name
+fn get_client_identity(
+    cer: &X509Certificate,
+    sans: &[GeneralName],
+) -> CerResult<Option<(&'static str, String)>> {
+    let spiffe = sans.iter().find_map(|name| match name {
+        GeneralName::URI(uri) if uri.starts_with("spiffe://") => Some(uri.to_string()),
+        _ => None,
+    });
+    if let Some(spiffe) = spiffe {
+        return Ok(Some(("spiffe", spiffe)));
+    }
+    let upn = sans.iter().find_map(|name| match name {
+        GeneralName::OtherName(oid, value) => parse_upn(oid, value),
+        _ => None,
+    });
+    if let Some(upn) = upn {
+        return Ok(Some(("upn", upn)));
+    }
+    match cer.subject().iter_common_name().next() {
+        Some(cn) => {
+            let cn = get_attribute_text(cn).map_err(CerError::CommonName)?;
+            Ok(Some(("cn", cn)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Text of a name attribute: the string types of as_str, BMPString and UniversalString,
+/// and the other types read as UTF-8
+pub fn get_attribute_text(attribute: &AttributeTypeAndValue) -> Result<String, Utf8Error> {
+    if let Ok(text) = attribute.as_str() {
+        return Ok(text.to_string());
+    }
+    let value = attribute.attr_value();
+    match decode_wide_string(value.tag(), value.data) {
+        Some(text) => Ok(text),
+        None => std::str::from_utf8(value.data).map(str::to_string),
+    }
+}
+
+// the name signing tools display: the common name, or the organization without one
+fn get_signer_name(cer: &X509Certificate, span: Span) -> CerResult<Value> {
+    let subject = cer.subject();
+    let signer = subject
+        .iter_common_name()
+        .next()
+        .or_else(|| subject.iter_organization().next());
+    match signer {
+        Some(signer) => {
+            let signer = get_attribute_text(signer).map_err(CerError::CommonName)?;
+            Ok(Value::string(signer, span))
+        }
+        None => Ok(Value::nothing(span)),
+    }
+}
+
+// S/MIME addresses are in rfc822Name SANs, older certificates have them in the subject
+fn get_email_addresses(
+    cer: &X509Certificate,
+    sans: &[GeneralName],
+    span: Span,
+) -> CerResult<Value> {
+    let mut addresses = sans
+        .iter()
+        .filter_map(|name| match name {
+            GeneralName::RFC822Name(address) => Some(address.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+    for address in cer.subject().iter_email() {
+        let address = get_attribute_text(address).map_err(CerError::Email)?;
+        if !addresses
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(&address))
+        {
+            addresses.push(address);
+        }
+    }
+    let addresses = addresses
+        .into_iter()
+        .map(|address| Value::string(address, span))
+        .collect();
+    Ok(Value::list(addresses, span))
+}
+
+pub fn get_certificate_ders(val: &str) -> CerResult<Vec<Vec<u8>>> {
+    Pem::iter_from_buffer(val.as_bytes())
+        .filter_map(|pem| match pem {
+            Ok(pem) => {
+                matches!(get_pem_kind(&pem), PemKind::Certificate).then_some(Ok(pem.contents))
+            }
+            Err(err) => Some(Err(CerError::Pem(err))),
+        })
+        .collect()
+}
+
+pub fn get_request_ders(val: &str) -> CerResult<Vec<Vec<u8>>> {
+    Pem::iter_from_buffer(val.as_bytes())
+        .filter_map(|pem| match pem {
+            Ok(pem) => matches!(get_pem_kind(&pem), PemKind::Request).then_some(Ok(pem.contents)),
+            Err(err) => Some(Err(CerError::Pem(err))),
+        })
+        .collect()
+}
+
+pub fn get_request_record(
+    csr: &X509CertificationRequest,
+    options: &RecordOptions,
+    span: Span,
+) -> CerResult<Record> {
+    for extension in get_request_extensions(csr) {
+        check_limit(
+            "bytes per extension",
+            extension.value.len(),
+            options.limits.max_extension_size,
+        )?;
+    }
+    let subject = &csr.certification_request_info.subject;
+    let mut record = Record::new();
+    record.push("cn", parse_common_names(subject, span)?);
+    record.push("subject", Value::string(subject.to_string(), span));
+    push_sans(&mut record, get_request_general_names(csr), options, span)?;
+    if options.rdns {
+        record.push("subject_rdns", parse_rdns(subject, span));
+    }
+    if let Some(analyzed_at) = options.analyzed_at {
+        record.push("analyzed_at", Value::date(analyzed_at.into(), span));
+    }
+    Ok(record)
+}
+
+pub fn get_request_extensions<'a>(csr: &'a X509CertificationRequest) -> &'a [X509Extension<'a>] {
+    csr.certification_request_info
+        .iter_attributes()
+        .find_map(|attribute| match attribute.parsed_attribute() {
+            ParsedCriAttribute::ExtensionRequest(requested) => {
+                Some(requested.extensions.as_slice())
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+pub fn get_eku_names(eku: &ExtendedKeyUsage) -> Vec<String> {
+    let mut names = [
+        (eku.any, "anyExtendedKeyUsage"),
+        (eku.server_auth, "serverAuth"),
+        (eku.client_auth, "clientAuth"),
+        (eku.code_signing, "codeSigning"),
+        (eku.email_protection, "emailProtection"),
+        (eku.time_stamping, "timeStamping"),
+        (eku.ocsp_signing, "OCSPSigning"),
+    ]
+    .iter()
+    .filter(|(present, _name)| *present)
+    .map(|(_present, name)| name.to_string())
+    .collect::<Vec<String>>();
+    names.extend(eku.other.iter().map(get_oid_name));
+    names
+}
+
+pub fn get_key_usage_names(ku: &KeyUsage) -> Vec<String> {
+    [
+        (ku.digital_signature(), "digitalSignature"),
+        (ku.non_repudiation(), "nonRepudiation"),
+        (ku.key_encipherment(), "keyEncipherment"),
+        (ku.data_encipherment(), "dataEncipherment"),
+        (ku.key_agreement(), "keyAgreement"),
+        (ku.key_cert_sign(), "keyCertSign"),
+        (ku.crl_sign(), "cRLSign"),
+        (ku.encipher_only(), "encipherOnly"),
+        (ku.decipher_only(), "decipherOnly"),
+    ]
+    .iter()
+    .filter(|(present, _name)| *present)
+    .map(|(_present, name)| name.to_string())
+    .collect()
+}
+
 pub fn get_thumbprint(pem: &Pem, span: Span) -> Value {
-    let contents = &pem.contents;
-    let val = sha1_smol::Sha1::from(contents).hexdigest();
+    get_der_thumbprint(&pem.contents, span)
+}
+
+// hexdigest formats byte by byte through fmt, encoding the raw digest avoids it
+pub fn get_der_thumbprint(der: &[u8], span: Span) -> Value {
+    let val = HEXLOWER.encode(&sha1_smol::Sha1::from(der).digest().bytes());
+    Value::string(val, span)
+}
+
+pub fn get_sha256(der: &[u8], span: Span) -> Value {
+    let val = HEXLOWER.encode(&Sha256::digest(der));
     Value::string(val, span)
 }
 
@@ -92,6 +437,12 @@ pub fn get_expiration(cer: &X509Certificate, span: Span) -> CerResult<Value> {
     Ok(value)
 }
 
+/// Nanoseconds from now until the expiration, saturating for a notAfter like 9999-12-31
+/// that is further away than the roughly 292 years a duration can hold
+pub fn get_residual(not_after: i64, now: i64) -> i64 {
+    not_after.saturating_sub(now).saturating_mul(1_000_000_000)
+}
+
 pub fn get_common_names(cer: &X509Certificate, span: Span) -> CerResult<Value> {
     let subject = cer.tbs_certificate.subject();
     parse_common_names(subject, span)
@@ -106,51 +457,193 @@ pub fn parse_common_names(name: &X509Name, span: Span) -> CerResult<Value> {
     let common_names = name
         .iter_common_name()
         .map(|cn| {
-            // Only NumericString, PrintableString, UTF8String and IA5String are considered here
-            let cn = match cn.as_str() {
-                Ok(as_str) => as_str,
-                // Other string types can be read using as_slice
-                Err(_err) => {
-                    let slice = cn.as_slice();
-                    std::str::from_utf8(slice).map_err(CerError::CommonName)?
-                }
-            };
-            Ok(Value::string(cn.to_string(), span))
+            let cn = get_attribute_text(cn).map_err(CerError::CommonName)?;
+            Ok(Value::string(cn, span))
         })
         .collect::<Result<Vec<Value>, CerError>>()?;
     let list = Value::list(common_names, span);
     Ok(list)
 }
 
+/// Ordered list of RDNs, each one a list of attributes, so multi-valued RDNs are kept
+pub fn parse_rdns(name: &X509Name, span: Span) -> Value {
+    let rdns = name
+        .iter()
+        .map(|rdn| {
+            let attributes = rdn
+                .iter()
+                .map(|attribute| {
+                    // binary values are kept as hex
+                    let value = get_attribute_text(attribute)
+                        .unwrap_or_else(|_err| HEXLOWER.encode(attribute.attr_value().data));
+                    let oid = attribute.attr_type();
+                    let mut record = Record::new();
+                    record.push("oid", Value::string(oid.to_id_string(), span));
+                    record.push("name", Value::string(get_oid_name(oid), span));
+                    record.push("value", Value::string(value, span));
+                    Value::record(record, span)
+                })
+                .collect();
+            Value::list(attributes, span)
+        })
+        .collect();
+    Value::list(rdns, span)
+}
+
 pub fn get_sans(cer: &X509Certificate, span: Span) -> CerResult<Value> {
     let sans = match cer
         .subject_alternative_name()
         .map_err(CerError::San)? // the Subject Alternative Name extension is invalid, or is present twice or more
     {
-        Some(sans) => sans
-            .value
-            .general_names
-            .iter()
-            .map(|name| {
-                match name {
-                    GeneralName::DNSName(name) => Ok(Value::string(name.to_string(), span)),
-                    _ => Err(CerError::San(X509Error::InvalidCertificate)), // we only handle DNS names
-                }
-            })
-            .collect::<CerResult<Vec<Value>>>()?,
+        Some(sans) => parse_general_names(&sans.value.general_names, span)?,
         None => Vec::new(), // no Subject Alternative Name extension was found in the certificate
     };
     let list = Value::list(sans, span);
     Ok(list)
 }
 
-pub fn get_pfx_friendly_name(cer: &CertContext) -> CerResult<String> {
-    cer.friendly_name().map_err(CerError::FriendlyName)
+/// Every SAN of a certificate as text, of all kinds, like the values of san_other
+pub fn get_all_sans(cer: &X509Certificate, span: Span) -> CerResult<Value> {
+    let sans = cer.subject_alternative_name().map_err(CerError::San)?;
+    let names = sans.map_or(&[][..], |sans| sans.value.general_names.as_slice());
+    get_san_strings(names, span)
+}
+
+/// Every requested SAN of a certificate request as text, of all kinds
+pub fn get_all_request_sans(csr: &X509CertificationRequest, span: Span) -> CerResult<Value> {
+    get_san_strings(get_request_general_names(csr), span)
 }
 
-pub fn get_pfx_thumbprint(cer: &CertContext) -> CerResult<String> {
-    let thumbprint = cer
-        .fingerprint(HashAlgorithm::sha1())
-        .map_err(CerError::Fingerprint)?;
-    Ok(HEXLOWER.encode(&thumbprint))
+fn get_san_strings(names: &[GeneralName], span: Span) -> CerResult<Value> {
+    let sans = names
+        .iter()
+        .map(|name| Ok(Value::string(get_san_string(name)?, span)))
+        .collect::<CerResult<Vec<Value>>>()?;
+    Ok(Value::list(sans, span))
+}
+
+pub fn get_request_general_names<'a>(csr: &'a X509CertificationRequest) -> &'a [GeneralName<'a>] {
+    csr.requested_extensions()
+        .and_then(|mut extensions| {
+            extensions.find_map(|extension| match extension {
+                ParsedExtension::SubjectAlternativeName(sans) => Some(sans),
+                _ => None,
+            })
+        })
+        .map_or(&[], |sans| sans.general_names.as_slice()) // no Subject Alternative Name was requested
+}
+
+/// Kinds of SAN, as named by --san-types
+pub const SAN_TYPES: [&str; 9] = [
+    "dns",
+    "ip",
+    "email",
+    "uri",
+    "dirname",
+    "othername",
+    "rid",
+    "x400",
+    "ediparty",
+];
+
+fn get_san_type(name: &GeneralName) -> &'static str {
+    match name {
+        GeneralName::DNSName(_) => "dns",
+        GeneralName::IPAddress(_) => "ip",
+        GeneralName::RFC822Name(_) => "email",
+        GeneralName::URI(_) => "uri",
+        GeneralName::DirectoryName(_) => "dirname",
+        GeneralName::OtherName(..) => "othername",
+        GeneralName::RegisteredID(_) => "rid",
+        GeneralName::X400Address(_) => "x400",
+        GeneralName::EDIPartyName(_) => "ediparty",
+    }
+}
+
+pub fn get_san_string(name: &GeneralName) -> CerResult<String> {
+    let string = match name {
+        GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => {
+            name.to_string()
+        }
+        GeneralName::IPAddress(ip) => parse_ip_address(ip)?,
+        GeneralName::DirectoryName(name) => name.to_string(),
+        // the value of an other name is left encoded, its syntax depends on the type
+        GeneralName::OtherName(oid, value) => format!("{}:{}", oid, HEXLOWER.encode(value)),
+        GeneralName::RegisteredID(oid) => oid.to_id_string(),
+        GeneralName::X400Address(any) | GeneralName::EDIPartyName(any) => HEXLOWER.encode(any.data),
+    };
+    Ok(string)
+}
+
+// without --san-types only DNS names and IP addresses are expected, as before the option
+fn push_sans(
+    record: &mut Record,
+    names: &[GeneralName],
+    options: &RecordOptions,
+    span: Span,
+) -> CerResult<()> {
+    let cut = |sans: &[Value]| !options.full_sans && sans.len() > SAN_PREVIEW;
+    let preview = |mut sans: Vec<Value>| {
+        if !options.full_sans {
+            sans.truncate(SAN_PREVIEW);
+        }
+        Value::list(sans, span)
+    };
+    let Some(types) = &options.san_types else {
+        let sans = parse_general_names(names, span)?;
+        let truncated = cut(&sans);
+        record.push("san", preview(sans));
+        record.push("san_count", Value::int(names.len() as i64, span));
+        // only cut lists carry the column, so the records of small certificates stay as they were
+        if truncated {
+            record.push("san_truncated", Value::bool(true, span));
+        }
+        return Ok(());
+    };
+    let mut sans = Vec::new();
+    let mut others = Vec::new();
+    for name in names {
+        let kind = get_san_type(name);
+        let value = Value::string(get_san_string(name)?, span);
+        if types.iter().any(|san_type| san_type == kind) {
+            sans.push(value);
+        } else {
+            let mut other = Record::new();
+            other.push("type", Value::string(kind, span));
+            other.push("value", value);
+            others.push(Value::record(other, span));
+        }
+    }
+    let truncated = cut(&sans);
+    record.push("san", preview(sans));
+    record.push("san_count", Value::int(names.len() as i64, span));
+    record.push("san_other", preview(others));
+    if truncated {
+        record.push("san_truncated", Value::bool(true, span));
+    }
+    Ok(())
+}
+
+pub fn parse_general_names(names: &[GeneralName], span: Span) -> CerResult<Vec<Value>> {
+    names
+        .iter()
+        .map(|name| {
+            match name {
+                GeneralName::DNSName(name) => Ok(Value::string(name.to_string(), span)),
+                GeneralName::IPAddress(ip) => Ok(Value::string(parse_ip_address(ip)?, span)),
+                _ => Err(CerError::San(X509Error::InvalidCertificate)), // we only handle DNS names and IP addresses
+            }
+        })
+        .collect()
+}
+
+pub fn parse_ip_address(ip: &[u8]) -> CerResult<String> {
+    let ip = if let Ok(ip) = <[u8; 4]>::try_from(ip) {
+        IpAddr::from(ip)
+    } else if let Ok(ip) = <[u8; 16]>::try_from(ip) {
+        IpAddr::from(ip)
+    } else {
+        return Err(CerError::San(X509Error::InvalidCertificate));
+    };
+    Ok(ip.to_string())
 }
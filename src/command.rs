@@ -1,12 +1,63 @@
-use chrono::Local;
+use chrono::{Local, Utc};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
-use nu_protocol::{record, Category, Example, LabeledError, Signature, SyntaxShape, Value};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value,
+};
 
 use crate::{
-    certificate::{get_pem_values, get_pfx_values},
+    certificate::{get_limits, get_pem_values, is_root, RecordOptions, SAN_TYPES},
+    core::{armor::check_strict, detect::detect},
+    digest::json_safe,
+    error::CerError,
+    ingest::{get_ingested_values, INGESTED_SOURCES},
+    pfx::get_pfx_values,
     plugin::CerPlugin,
+    preset::{get_preset, select_columns, KEY_COLUMNS},
+    provenance::add_provenance,
+    redact::get_redact_policy,
+    secret::Secret,
 };
 
+mod cert_manager;
+mod coverage;
+mod csr_compare;
+mod csr_verify;
+mod dedup;
+mod dn;
+mod expiry;
+mod explain_oid;
+mod key_export;
+mod key_strength;
+mod kube;
+mod ldap;
+mod lint;
+mod merge;
+mod policy;
+mod scan_config;
+mod snapshot;
+mod spiffe;
+mod strength;
+
+pub use cert_manager::CerCertManager;
+pub use coverage::CerCoverage;
+pub use csr_compare::CerCsrCompare;
+pub use csr_verify::CerCsrVerify;
+pub use dedup::CerDedup;
+pub use dn::CerDn;
+pub use expiry::CerExpiry;
+pub use explain_oid::CerExplainOid;
+pub use key_export::CerKeyExport;
+pub use key_strength::CerKeyStrength;
+pub use kube::CerKube;
+pub use ldap::CerLdap;
+pub use lint::CerLint;
+pub use merge::CerMerge;
+pub use policy::CerPolicyCheck;
+pub use scan_config::CerScanConfig;
+pub use snapshot::{CerCompareStores, CerSnapshot, CerSnapshotDiff, CerSnapshotVerify};
+pub use spiffe::CerSpiffeVerify;
+pub use strength::CerStrength;
+
 pub struct Cer;
 
 impl SimplePluginCommand for Cer {
@@ -17,10 +68,14 @@ impl SimplePluginCommand for Cer {
     }
 
     fn usage(&self) -> &str {
-        "Shows details of a cer/pfx"
+        "Shows details of a cer/csr/pfx"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Text input is read as PEM and binary input as pfx. Records are read as the JSON output of other tools, like `istioctl proxy-config secret <pod> -o json | from json | cer --list` or `vault write -format=json pki/issue/web common_name=web.example.com | from json | cer --list`, each certificate getting the columns of where it was found. The private keys of pfx input are never returned, --with-key-info only adds their metadata. Presets are overridden or added in the plugin config, like $env.config.plugins.cer.presets = {inventory: [cn san expiration sha256 source]}."
     }
 
-    fn examples(&self) -> Vec<nu_protocol::Example> {
+    fn examples(&self) -> Vec<nu_protocol::Example<'_>> {
         vec![Example {
             example: "open path/to/certificate.cer | cer",
             description: "shows the details of the first certificate in the certificate.cer file",
@@ -28,10 +83,12 @@ impl SimplePluginCommand for Cer {
                     "cn" => Value::test_string("cer.com"),
                     "subject" => Value::test_string("CN=cer.com, Email=cer@example.com, O=Example"),
                     "san" => Value::test_string("alternative.com"),
+                    "san_count" => Value::test_int(1),
                     "ca" => Value::test_string("ca.com"),
                     "ca_subject" => Value::test_string("CN=ca.com, Email=ca@example.com, O=Example"),
                     "expiration" => Value::test_date(Local::now().into()),
-                    "thumbprint" => Value::test_string("8910651b144734559872b321419ff87233fd4392")))),
+                    "thumbprint" => Value::test_string("8910651b144734559872b321419ff87233fd4392"),
+                    "sha256" => Value::test_string("3f1c0a6d8b5f1e6a2f8c4d2b9e7a5c3d1f0e9b8a7c6d5e4f3a2b1c0d9e8f7a6b")))),
         }]
     }
 
@@ -42,57 +99,289 @@ impl SimplePluginCommand for Cer {
                 "return all certificates as a list instead of only the first",
                 Some('a'),
             )
+            .named(
+                "part",
+                SyntaxShape::String,
+                "return only the leaf, intermediates, root or chain (leaf and intermediates), matched by issuer",
+                None,
+            )
+            .named(
+                "san-types",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "kinds of SAN in the san column, with the others in san_other: dns, ip, email, uri, dirname, othername, rid, x400 or ediparty",
+                None,
+            )
+            .switch(
+                "full-sans",
+                "list every subject alternative name, instead of the first 100 with the total in san_count and san_truncated set",
+                None,
+            )
+            .switch(
+                "rdns",
+                "add the subject and ca subject as ordered lists of RDNs",
+                None,
+            )
+            .switch(
+                "analyzed-at",
+                "add the UTC time of the analysis, to tell apart concatenated snapshots",
+                None,
+            )
+            .named(
+                "preset",
+                SyntaxShape::String,
+                "return the columns of inventory, security, tls-debug or a preset of the plugin config",
+                None,
+            )
+            .switch(
+                "provenance",
+                "add a provenance column telling which columns are extracted from the certificate, computed, heuristic or from the input",
+                None,
+            )
+            .switch(
+                "json-safe",
+                "sort the columns and write dates and binaries as strings, so `to json` is byte-stable across runs and machines",
+                None,
+            )
+            .switch(
+                "redact",
+                "hash internal hostnames, private addresses, email addresses and serial numbers, following the redact policy of the plugin config",
+                None,
+            )
+            .switch(
+                "rfc7468-strict",
+                "reject PEM text that does not follow the strict RFC 7468 grammar",
+                None,
+            )
+            .switch(
+                "detect-only",
+                "report what format the input appears to be and why, without parsing it",
+                None,
+            )
             .named(
                 "password",
                 SyntaxShape::String,
                 "password to read the certificate",
                 Some('p'),
             )
+            .switch(
+                "with-key-info",
+                "add a private_key column with the algorithm and size of the private key of each pfx certificate",
+                None,
+            )
             .category(Category::System)
     }
 
     fn search_terms(&self) -> Vec<&str> {
-        vec!["certificate", "cer", "pfx"]
+        vec![
+            "certificate",
+            "cer",
+            "csr",
+            "pfx",
+            "istio",
+            "envoy",
+            "vault",
+        ]
     }
 
     fn run(
         &self,
         _plugin: &CerPlugin,
-        _engine: &EngineInterface,
+        engine: &EngineInterface,
         call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
         let span = input.span();
-        if let Value::String { val, .. } = input {
-            let values = get_pem_values(val, span)?;
-            if call.has_flag("list")? {
-                let list = Value::list(values, span);
-                Ok(list)
-            } else {
-                values
-                    .first()
-                    .cloned()
-                    .ok_or(LabeledError::new("no certificates in file"))
+        if call.has_flag("detect-only")? {
+            return match input {
+                Value::String { val, .. } => Ok(get_detection(val.as_bytes(), true, span)),
+                Value::Binary { val, .. } => Ok(get_detection(val, false, span)),
+                _ => Err(
+                    LabeledError::new("Expected certificate input from pipeline").with_label(
+                        format!("requires certificate input; got {}", input.get_type()),
+                        call.head,
+                    ),
+                ),
+            };
+        }
+        let part = call.get_flag::<String>("part")?;
+        if let Some(part) = &part {
+            if !["leaf", "intermediates", "root", "chain"].contains(&part.as_str()) {
+                return Err(LabeledError::new("invalid part").with_label(
+                    format!("expected leaf, intermediates, root or chain; got {}", part),
+                    call.head,
+                ));
+            }
+        }
+        let san_types = call.get_flag::<Vec<String>>("san-types")?;
+        if let Some(san_type) = san_types
+            .iter()
+            .flatten()
+            .find(|san_type| !SAN_TYPES.contains(&san_type.as_str()))
+        {
+            return Err(LabeledError::new("invalid SAN type").with_label(
+                format!("expected {}; got {}", SAN_TYPES.join(", "), san_type),
+                call.head,
+            ));
+        }
+        let config = engine.get_plugin_config()?;
+        let preset = match call.get_flag::<String>("preset")? {
+            Some(name) => Some(get_preset(config.as_ref(), &name).ok_or_else(|| {
+                LabeledError::new("unknown preset").with_label(
+                    format!(
+                        "expected inventory, security, tls-debug or a preset of the plugin config; got {}",
+                        name
+                    ),
+                    call.head,
+                )
+            })?),
+            None => None,
+        };
+        let mut options = RecordOptions {
+            rdns: call.has_flag("rdns")?,
+            san_types,
+            analyzed_at: call.has_flag("analyzed-at")?.then(Utc::now),
+            key_details: preset.as_ref().is_some_and(|columns| {
+                columns
+                    .iter()
+                    .any(|column| KEY_COLUMNS.contains(&column.as_str()))
+            }),
+            full_sans: call.has_flag("full-sans")?,
+            limits: get_limits(config.clone()),
+        };
+        let values = match input {
+            Value::String { val, .. } => {
+                if call.has_flag("rfc7468-strict")? {
+                    check_strict(val).map_err(CerError::from)?;
+                }
+                get_pem_values(val, &options, span)?
+            }
+            Value::Binary { val, .. } => {
+                let password = call
+                    .get_flag_value("password")
+                    .map(|password| password.into_string().map(Secret::new))
+                    .transpose()
+                    .map_err(CerError::Password)?;
+                let key_info = call.has_flag("with-key-info")?;
+                get_pfx_values(val, password.as_deref(), key_info, &options, span)?
+            }
+            Value::Record { val, .. } => {
+                // the certificates other tools hand out often carry URI SANs, like SPIFFE IDs
+                options
+                    .san_types
+                    .get_or_insert_with(|| vec!["dns".into(), "ip".into()]);
+                get_ingested_values(val, &options, span)?.ok_or_else(|| {
+                    LabeledError::new("unknown certificate record").with_label(
+                        format!("expected the JSON of {}", INGESTED_SOURCES),
+                        call.head,
+                    )
+                })?
             }
-        } else if let Value::Binary { val, .. } = input {
-            let password = call.get_flag_value("password");
-            let values = get_pfx_values(val, password, span)?;
-            if call.has_flag("list")? {
-                let list = Value::list(values, span);
-                Ok(list)
-            } else {
-                values
-                    .first()
-                    .cloned()
-                    .ok_or(LabeledError::new("no certificates in file"))
+            _ => {
+                return Err(
+                    LabeledError::new("Expected certificate input from pipeline").with_label(
+                        format!("requires certificate input; got {}", input.get_type()),
+                        call.head,
+                    ),
+                )
             }
+        };
+        let output = if let Some(part) = &part {
+            get_part(values, part, span)
+                .ok_or_else(|| LabeledError::new(format!("no {} certificate in file", part)))?
+        } else if call.has_flag("list")? {
+            Value::list(values, span)
         } else {
-            Err(
-                LabeledError::new("Expected certificate input from pipeline").with_label(
-                    format!("requires certificate input; got {}", input.get_type()),
-                    call.head,
-                ),
-            )
+            values
+                .first()
+                .cloned()
+                .ok_or(LabeledError::new("no certificates in file"))?
+        };
+        let output = match &preset {
+            Some(columns) => select_columns(output, columns),
+            None => output,
+        };
+        let output = if call.has_flag("redact")? {
+            get_redact_policy(config.as_ref()).redact(output)
+        } else {
+            output
+        };
+        let output = if call.has_flag("provenance")? {
+            add_provenance(output)
+        } else {
+            output
+        };
+        if call.has_flag("json-safe")? {
+            Ok(json_safe(output))
+        } else {
+            Ok(output)
+        }
+    }
+}
+
+/// Walks from the leaf, the certificate that issued none of the others, up to the root
+fn get_chain(values: &[Value]) -> (Vec<&Value>, Option<&Value>) {
+    let issues_other = |val: &&Value| {
+        values.iter().any(|other| {
+            !std::ptr::eq(other, *val)
+                && other.get_data_by_key("ca_subject") == val.get_data_by_key("subject")
+        })
+    };
+    let mut chain = Vec::new();
+    let mut next = values.iter().find(|val| !issues_other(val));
+    while let Some(val) = next {
+        if is_root(val) && !chain.is_empty() {
+            return (chain, Some(val));
+        }
+        chain.push(val);
+        // a chain never holds more certificates than the input, even with an issuer loop
+        if chain.len() == values.len() {
+            break;
+        }
+        let issuer = val.get_data_by_key("ca_subject");
+        next = values
+            .iter()
+            .find(|other| issuer.is_some() && other.get_data_by_key("subject") == issuer);
+        if next.is_some_and(|next| std::ptr::eq(next, val)) {
+            // a self-signed leaf is its own root
+            return (chain, Some(val));
         }
     }
+    let root = values.iter().find(|val| is_root(val));
+    (chain, root)
+}
+
+/// The leaf or root record, or the list of intermediates or chain records, None without a leaf or root
+fn get_part(values: Vec<Value>, part: &str, span: Span) -> Option<Value> {
+    let (chain, root) = get_chain(&values);
+    let list = |vals: &[&Value]| Value::list(vals.iter().map(|val| (*val).clone()).collect(), span);
+    match part {
+        "leaf" => chain.first().map(|val| (*val).clone()),
+        "intermediates" => Some(list(chain.get(1..).unwrap_or_default())),
+        "root" => root.cloned(),
+        _ => Some(list(&chain)),
+    }
+}
+
+fn get_detection(data: &[u8], text: bool, span: Span) -> Value {
+    let detection = detect(data, text);
+    let strings = |strings: Vec<String>| {
+        let values = strings
+            .into_iter()
+            .map(|string| Value::string(string, span))
+            .collect();
+        Value::list(values, span)
+    };
+    let magic = data
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    let mut record = Record::new();
+    record.push("format", Value::string(detection.format, span));
+    record.push("reasons", strings(detection.reasons));
+    record.push("pem_labels", strings(detection.labels));
+    record.push("size", Value::filesize(data.len() as i64, span));
+    record.push("magic", Value::string(magic, span));
+    record.push("entropy", Value::float(detection.entropy, span));
+    Value::record(record, span)
 }
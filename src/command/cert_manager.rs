@@ -0,0 +1,274 @@
+use chrono::DateTime;
+use data_encoding::BASE64;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, Value};
+use x509_parser::{certification_request::X509CertificationRequest, pem::Pem, prelude::FromDer};
+
+use crate::{
+    certificate::{
+        get_all_request_sans, get_all_sans, get_common_names, get_expiration, parse_common_names,
+    },
+    error::CerError,
+    ingest::{get_path, get_text},
+    plugin::CerPlugin,
+};
+
+pub struct CerCertManager;
+
+impl SimplePluginCommand for CerCertManager {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer cert-manager"
+    }
+
+    fn usage(&self) -> &str {
+        "Shows the renewal status of cert-manager certificates, certificate requests and their secrets"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Certificates report their Ready condition, renewal time and expiration, certificate requests their decoded CSR and issued certificate, and TLS secrets the certificate they hold. The san column holds every kind of SAN, like the SPIFFE URIs of istio-csr requests. Other objects are left out."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "kubectl get certificates -A -o json | from json | cer cert-manager | where ready != true",
+                description: "shows the certificates cert-manager fails to issue, with the reason",
+                result: None,
+            },
+            Example {
+                example: "kubectl get certificaterequests,secrets -n web -o json | from json | cer cert-manager",
+                description: "decodes the requests and issued secrets of a namespace",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "kubernetes", "cert-manager", "renewal"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        // a kubectl list, a single object or a nushell list of objects
+        let objects = match input {
+            Value::Record { val, .. } => match val.get("items") {
+                Some(Value::List { vals, .. }) => vals.clone(),
+                _ => vec![input.clone()],
+            },
+            Value::List { vals, .. } => vals.clone(),
+            _ => {
+                return Err(
+                    LabeledError::new("Expected kubernetes objects from pipeline").with_label(
+                        format!(
+                            "requires the output of kubectl get -o json | from json; got {}",
+                            input.get_type()
+                        ),
+                        call.head,
+                    ),
+                )
+            }
+        };
+        let values = objects
+            .iter()
+            .filter_map(|object| get_object_value(object, span))
+            .collect();
+        Ok(Value::list(values, span))
+    }
+}
+
+fn get_date(value: Option<&Value>, span: Span) -> Value {
+    match value {
+        Some(Value::Date { val, .. }) => Value::date(*val, span),
+        Some(Value::String { val, .. }) => DateTime::parse_from_rfc3339(val)
+            .map_or(Value::nothing(span), |date| Value::date(date, span)),
+        _ => Value::nothing(span),
+    }
+}
+
+fn decode_pem(data: &str) -> Result<Pem, CerError> {
+    let data = BASE64
+        .decode(data.trim().as_bytes())
+        .map_err(CerError::Base64)?;
+    Pem::iter_from_buffer(&data)
+        .next()
+        .ok_or(CerError::NoCertificate("secret"))?
+        .map_err(CerError::Pem)
+}
+
+// the Ready condition, and the reason and message of the latest condition that is not met
+fn push_conditions(object: &Value, span: Span, status: &mut Record) {
+    let conditions = get_path(object, &["status", "conditions"])
+        .and_then(|conditions| conditions.as_list().ok())
+        .unwrap_or_default();
+    let condition = |kind: &str| {
+        conditions
+            .iter()
+            .find(|condition| get_text(get_path(condition, &["type"])).as_deref() == Some(kind))
+    };
+    if let Some(ready) = condition("Ready") {
+        let is_ready = get_text(get_path(ready, &["status"])).as_deref() == Some("True");
+        status.push("ready", Value::bool(is_ready, span));
+        if !is_ready {
+            // a denied or failed request says why in its own condition
+            let reason = condition("Denied")
+                .or(condition("InvalidRequest"))
+                .unwrap_or(ready);
+            for (column, field) in [("reason", "reason"), ("message", "message")] {
+                if let Some(text) = get_text(get_path(reason, &[field])) {
+                    status.push(column, Value::string(text, span));
+                }
+            }
+        }
+    }
+}
+
+fn push_certificate(data: &str, span: Span, status: &mut Record) -> Result<(), CerError> {
+    let pem = decode_pem(data)?;
+    let cer = pem.parse_x509().map_err(CerError::Parse)?;
+    status.push("cn", get_common_names(&cer, span)?);
+    status.push("san", get_all_sans(&cer, span)?);
+    status.push("not_after", get_expiration(&cer, span)?);
+    Ok(())
+}
+
+fn get_object_status(
+    kind: &str,
+    object: &Value,
+    span: Span,
+    status: &mut Record,
+) -> Result<(), CerError> {
+    let strings = |path: &[&str]| {
+        get_path(object, path)
+            .and_then(|values| values.as_list().ok())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|value| Some(Value::string(value.as_str().ok()?, span)))
+            .collect::<Vec<Value>>()
+    };
+    match kind {
+        "Certificate" => {
+            push_conditions(object, span, status);
+            let cn = get_text(get_path(object, &["spec", "commonName"]))
+                .map(|cn| Value::string(cn, span))
+                .into_iter()
+                .collect();
+            status.push("cn", Value::list(cn, span));
+            // the same kinds, in the same order, as the SANs cert-manager issues
+            let mut sans = Vec::new();
+            for field in ["dnsNames", "ipAddresses", "uris", "emailAddresses"] {
+                sans.extend(strings(&["spec", field]));
+            }
+            let other_names = get_path(object, &["spec", "otherNames"])
+                .and_then(|names| names.as_list().ok())
+                .unwrap_or_default();
+            for name in other_names {
+                let oid = get_text(get_path(name, &["oid"]));
+                let value = get_text(get_path(name, &["utf8Value"]));
+                if let (Some(oid), Some(value)) = (oid, value) {
+                    sans.push(Value::string(format!("{}:{}", oid, value), span));
+                }
+            }
+            status.push("san", Value::list(sans, span));
+            status.push(
+                "not_after",
+                get_date(get_path(object, &["status", "notAfter"]), span),
+            );
+            status.push(
+                "renewal_time",
+                get_date(get_path(object, &["status", "renewalTime"]), span),
+            );
+        }
+        "CertificateRequest" => {
+            push_conditions(object, span, status);
+            if let Some(request) = get_text(get_path(object, &["spec", "request"])) {
+                let pem = decode_pem(&request)?;
+                let (_rem, csr) =
+                    X509CertificationRequest::from_der(&pem.contents).map_err(CerError::Request)?;
+                let subject = &csr.certification_request_info.subject;
+                status.push("cn", parse_common_names(subject, span)?);
+                status.push("san", get_all_request_sans(&csr, span)?);
+            }
+            // the certificate is only there once the request is issued
+            if let Some(certificate) = get_text(get_path(object, &["status", "certificate"])) {
+                let pem = decode_pem(&certificate)?;
+                let cer = pem.parse_x509().map_err(CerError::Parse)?;
+                status.push("not_after", get_expiration(&cer, span)?);
+            }
+        }
+        _ => {
+            if let Some(data) = get_text(get_path(object, &["data", "tls.crt"])) {
+                push_certificate(&data, span, status)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn get_object_value(object: &Value, span: Span) -> Option<Value> {
+    let kind = get_text(get_path(object, &["kind"]))?;
+    // cert-manager records the certificate of its requests and secrets in an annotation
+    let annotation = get_path(
+        object,
+        &[
+            "metadata",
+            "annotations",
+            "cert-manager.io/certificate-name",
+        ],
+    );
+    let certificate = match kind.as_str() {
+        "Certificate" => get_text(get_path(object, &["metadata", "name"])),
+        "CertificateRequest" => get_text(annotation),
+        "Secret" => {
+            let tls = get_text(get_path(object, &["type"])).as_deref() == Some("kubernetes.io/tls");
+            if !tls {
+                return None;
+            }
+            get_text(annotation)
+        }
+        _ => return None,
+    };
+    let text =
+        |text: Option<String>| text.map_or(Value::nothing(span), |text| Value::string(text, span));
+    let mut record = Record::new();
+    record.push("kind", Value::string(&kind, span));
+    record.push(
+        "namespace",
+        text(get_text(get_path(object, &["metadata", "namespace"]))),
+    );
+    record.push(
+        "name",
+        text(get_text(get_path(object, &["metadata", "name"]))),
+    );
+    record.push("certificate", text(certificate));
+    let mut status = Record::new();
+    let error = match get_object_status(&kind, object, span, &mut status) {
+        Ok(()) => Value::nothing(span),
+        Err(err) => Value::string(err.details(), span),
+    };
+    for column in [
+        "ready",
+        "reason",
+        "message",
+        "cn",
+        "san",
+        "not_after",
+        "renewal_time",
+    ] {
+        let value = status.get(column).cloned().unwrap_or(Value::nothing(span));
+        record.push(column, value);
+    }
+    record.push("error", error);
+    Some(Value::record(record, span))
+}
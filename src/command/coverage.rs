@@ -0,0 +1,72 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Value};
+
+use crate::{coverage::get_coverage, plugin::CerPlugin};
+
+pub struct CerCoverage;
+
+impl SimplePluginCommand for CerCoverage {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer coverage"
+    }
+
+    fn usage(&self) -> &str {
+        "Reports which hostnames are covered by which certificates, the uncovered hostnames and the certificates covering none"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Hostnames are matched against the san column, or the cn column of certificates without SANs. The san column must be complete, so certificates with more than 100 SANs must come from `cer --full-sans`. A wildcard covers exactly one label, so *.example.com covers www.example.com but neither example.com nor a.b.example.com."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "ls *.pem | each { open $in.name | cer --list --full-sans } | flatten | cer coverage --hosts (open hosts.txt | lines) | get uncovered",
+                description: "lists the hostnames of an inventory that no certificate covers",
+                result: None,
+            },
+            Example {
+                example: "open bundle.pem | cer --list --full-sans | cer coverage --hosts [www.example.com api.example.com] | get unused.cn",
+                description: "finds the certificates of a bundle that cover none of the hostnames",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required_named(
+                "hosts",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "hostnames to cover, like the lines of a DNS zone export",
+                None,
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "coverage", "hostname", "wildcard", "renewal"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let hosts = call.get_flag::<Vec<String>>("hosts")?.unwrap_or_default();
+        let Value::List { vals, .. } = input else {
+            return Err(
+                LabeledError::new("Expected certificate list input from pipeline").with_label(
+                    format!("requires a list of certificates; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        Ok(get_coverage(vals, hosts, span)?)
+    }
+}
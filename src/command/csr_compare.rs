@@ -0,0 +1,205 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{
+    certificate::X509Certificate,
+    certification_request::X509CertificationRequest,
+    extensions::{GeneralName, ParsedExtension},
+    pem::Pem,
+    prelude::FromDer,
+};
+
+use crate::{
+    certificate::{
+        get_eku_names, get_request_ders, get_request_extensions, get_request_general_names,
+        get_san_string,
+    },
+    core::{host::normalize_host, oid::get_oid_name},
+    error::CerError,
+    plugin::CerPlugin,
+};
+
+pub struct CerCsrCompare;
+
+impl SimplePluginCommand for CerCsrCompare {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer csr compare"
+    }
+
+    fn usage(&self) -> &str {
+        "Compares a certificate request with the certificate issued from it"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open request.csr | cer csr compare (open issued.cer)",
+            description:
+                "shows which requested SANs, EKUs and extensions the CA honored, dropped or added",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "certificate",
+                SyntaxShape::String,
+                "issued certificate, in PEM format",
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "csr", "request", "compare", "eku"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let der = match input {
+            Value::String { val, .. } => get_request_ders(val)?.into_iter().next(),
+            Value::Binary { val, .. } => Some(val.clone()),
+            _ => {
+                return Err(
+                    LabeledError::new("Expected certificate request input from pipeline")
+                        .with_label(
+                            format!(
+                                "requires certificate request input; got {}",
+                                input.get_type()
+                            ),
+                            call.head,
+                        ),
+                )
+            }
+        }
+        .ok_or(LabeledError::new("no certificate requests in file"))?;
+        let (_rem, csr) = X509CertificationRequest::from_der(&der).map_err(CerError::Request)?;
+        let certificate: String = call.req(0)?;
+        let pem = Pem::iter_from_buffer(certificate.as_bytes())
+            .next()
+            .ok_or(CerError::NoCertificate("argument"))?
+            .map_err(CerError::Pem)?;
+        let cer = pem.parse_x509().map_err(CerError::Parse)?;
+        let record = get_compare_record(&csr, &cer, span)?;
+        Ok(Value::record(record, span))
+    }
+}
+
+/// Each value with the key it compares by
+type Keyed = Vec<(String, String)>;
+
+fn keyed(values: Vec<String>) -> Keyed {
+    values
+        .into_iter()
+        .map(|value| (value.clone(), value))
+        .collect()
+}
+
+// DNS names compare without case or a trailing dot, the other kinds as they are
+fn get_keyed_sans(names: &[GeneralName]) -> Result<Keyed, CerError> {
+    names
+        .iter()
+        .map(|name| {
+            let text = get_san_string(name)?;
+            let key = match name {
+                GeneralName::DNSName(dns) => normalize_host(dns),
+                _ => text.clone(),
+            };
+            Ok((text, key))
+        })
+        .collect()
+}
+
+fn push_comparison(record: &mut Record, name: &str, requested: &Keyed, issued: &Keyed, span: Span) {
+    let list = |values: Vec<&(String, String)>| {
+        Value::list(
+            values
+                .into_iter()
+                .map(|(value, _key)| Value::string(value, span))
+                .collect(),
+            span,
+        )
+    };
+    let contains = |values: &Keyed, key: &String| values.iter().any(|(_value, other)| other == key);
+    let honored = requested
+        .iter()
+        .filter(|(_value, key)| contains(issued, key));
+    let dropped = requested
+        .iter()
+        .filter(|(_value, key)| !contains(issued, key));
+    let added = issued
+        .iter()
+        .filter(|(_value, key)| !contains(requested, key));
+    record.push(format!("{}_honored", name), list(honored.collect()));
+    record.push(format!("{}_dropped", name), list(dropped.collect()));
+    record.push(format!("{}_added", name), list(added.collect()));
+}
+
+fn get_compare_record(
+    csr: &X509CertificationRequest,
+    cer: &X509Certificate,
+    span: Span,
+) -> Result<Record, CerError> {
+    let info = &csr.certification_request_info;
+    let requested_extensions = get_request_extensions(csr);
+
+    let requested_sans = get_keyed_sans(get_request_general_names(csr))?;
+    let issued_sans = cer
+        .subject_alternative_name()
+        .map_err(CerError::San)?
+        .map_or(Ok(Vec::new()), |sans| {
+            get_keyed_sans(&sans.value.general_names)
+        })?;
+
+    let requested_ekus = requested_extensions
+        .iter()
+        .find_map(|extension| match extension.parsed_extension() {
+            ParsedExtension::ExtendedKeyUsage(eku) => Some(get_eku_names(eku)),
+            _ => None,
+        })
+        .map(keyed)
+        .unwrap_or_default();
+    let issued_ekus = cer
+        .extended_key_usage()
+        .map_err(CerError::Extension)?
+        .map(|eku| keyed(get_eku_names(eku.value)))
+        .unwrap_or_default();
+
+    let requested_extensions = keyed(
+        requested_extensions
+            .iter()
+            .map(|extension| get_oid_name(&extension.oid))
+            .collect(),
+    );
+    let issued_extensions = keyed(
+        cer.iter_extensions()
+            .map(|extension| get_oid_name(&extension.oid))
+            .collect(),
+    );
+
+    let mut record = Record::new();
+    record.push(
+        "same_key",
+        Value::bool(info.subject_pki.raw == cer.public_key().raw, span),
+    );
+    record.push(
+        "same_subject",
+        Value::bool(info.subject.as_raw() == cer.subject().as_raw(), span),
+    );
+    push_comparison(&mut record, "san", &requested_sans, &issued_sans, span);
+    push_comparison(&mut record, "eku", &requested_ekus, &issued_ekus, span);
+    push_comparison(
+        &mut record,
+        "extension",
+        &requested_extensions,
+        &issued_extensions,
+        span,
+    );
+    Ok(record)
+}
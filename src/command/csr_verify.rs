@@ -0,0 +1,242 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{
+    certification_request::X509CertificationRequest,
+    cri_attributes::ParsedCriAttribute,
+    der_parser::{parse_der, Oid},
+    oid_registry::{OID_PKCS9_CHALLENGE_PASSWORD, OID_PKCS9_UNSTRUCTURED_NAME},
+    prelude::FromDer,
+};
+
+use crate::{
+    certificate::{get_request_ders, parse_common_names},
+    core::key::{get_key_algorithm, get_key_size},
+    error::CerError,
+    plugin::CerPlugin,
+    secret::Secret,
+};
+
+pub struct CerCsrVerify;
+
+impl SimplePluginCommand for CerCsrVerify {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer csr verify"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks the self-signature, key strength and attributes of a certificate request"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open request.csr | cer csr verify --min-rsa-bits 3072",
+            description: "verifies the request and rejects RSA keys shorter than 3072 bits",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .switch(
+                "list",
+                "return all certificate requests as a list instead of only the first",
+                Some('a'),
+            )
+            .named(
+                "min-rsa-bits",
+                SyntaxShape::Int,
+                "minimum size of RSA and DSA keys (default: 2048)",
+                None,
+            )
+            .named(
+                "min-ec-bits",
+                SyntaxShape::Int,
+                "minimum size of elliptic curve keys (default: 256)",
+                None,
+            )
+            .named(
+                "challenge-password",
+                SyntaxShape::String,
+                "expected challengePassword, the attribute must be absent otherwise",
+                None,
+            )
+            .named(
+                "unstructured-name",
+                SyntaxShape::String,
+                "expected unstructuredName, the attribute must be absent otherwise",
+                None,
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "csr", "request", "verify", "signature"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let policy = RequestPolicy {
+            min_rsa_bits: call.get_flag::<i64>("min-rsa-bits")?.unwrap_or(2048),
+            min_ec_bits: call.get_flag::<i64>("min-ec-bits")?.unwrap_or(256),
+            challenge_password: call
+                .get_flag_value("challenge-password")
+                .map(|password| password.into_string().map(Secret::new))
+                .transpose()
+                .map_err(CerError::Password)?,
+            unstructured_name: call.get_flag("unstructured-name")?,
+        };
+        let ders = match input {
+            Value::String { val, .. } => get_request_ders(val)?,
+            Value::Binary { val, .. } => vec![val.clone()],
+            _ => {
+                return Err(
+                    LabeledError::new("Expected certificate request input from pipeline")
+                        .with_label(
+                            format!(
+                                "requires certificate request input; got {}",
+                                input.get_type()
+                            ),
+                            call.head,
+                        ),
+                )
+            }
+        };
+        let values = ders
+            .iter()
+            .map(|der| {
+                let (_rem, csr) =
+                    X509CertificationRequest::from_der(der).map_err(CerError::Request)?;
+                let record = get_verify_record(&csr, &policy, span)?;
+                Ok(Value::record(record, span))
+            })
+            .collect::<Result<Vec<Value>, CerError>>()?;
+        if call.has_flag("list")? {
+            Ok(Value::list(values, span))
+        } else {
+            values
+                .first()
+                .cloned()
+                .ok_or(LabeledError::new("no certificate requests in file"))
+        }
+    }
+}
+
+struct RequestPolicy {
+    min_rsa_bits: i64,
+    min_ec_bits: i64,
+    challenge_password: Option<Secret>,
+    unstructured_name: Option<String>,
+}
+
+fn get_attribute_string(csr: &X509CertificationRequest, oid: &Oid) -> Option<String> {
+    let attribute = csr.certification_request_info.find_attribute(oid)?;
+    if let ParsedCriAttribute::ChallengePassword(password) = attribute.parsed_attribute() {
+        return Some(password.0.clone());
+    }
+    // attribute values are a SET, only the first value is considered
+    let (_rem, set) = parse_der(attribute.value).ok()?;
+    let value = set.as_set().ok()?.first()?.as_str().ok()?;
+    Some(value.to_string())
+}
+
+fn check_attribute(
+    name: &str,
+    actual: Option<&str>,
+    expected: Option<&str>,
+    issues: &mut Vec<String>,
+) {
+    match (actual, expected) {
+        (Some(_), None) => issues.push(format!("unexpected {} attribute", name)),
+        (None, Some(_)) => issues.push(format!("missing {} attribute", name)),
+        (Some(actual), Some(expected)) if actual != expected => issues.push(format!(
+            "{} attribute does not have the expected value",
+            name
+        )),
+        _ => {}
+    }
+}
+
+fn get_verify_record(
+    csr: &X509CertificationRequest,
+    policy: &RequestPolicy,
+    span: Span,
+) -> Result<Record, CerError> {
+    let info = &csr.certification_request_info;
+    let mut issues = Vec::new();
+
+    let signature_valid = match csr.verify_signature() {
+        Ok(()) => true,
+        Err(err) => {
+            issues.push(format!("invalid self-signature: {}", err));
+            false
+        }
+    };
+
+    let algorithm = get_key_algorithm(&info.subject_pki);
+    let size = get_key_size(&info.subject_pki) as i64;
+    let key_strong = match algorithm.as_str() {
+        "rsa" | "rsa-pss" | "dsa" => size >= policy.min_rsa_bits,
+        "ed25519" | "ed448" => true,
+        algorithm if algorithm.starts_with("ec") => size >= policy.min_ec_bits,
+        _ => false,
+    };
+    if !key_strong {
+        issues.push(format!(
+            "{} key of {} bits does not meet the policy",
+            algorithm, size
+        ));
+    }
+
+    // the challenge password itself is never returned
+    let challenge_password =
+        get_attribute_string(csr, &OID_PKCS9_CHALLENGE_PASSWORD).map(Secret::new);
+    let unstructured_name = get_attribute_string(csr, &OID_PKCS9_UNSTRUCTURED_NAME);
+    check_attribute(
+        "challengePassword",
+        challenge_password.as_deref(),
+        policy.challenge_password.as_deref(),
+        &mut issues,
+    );
+    check_attribute(
+        "unstructuredName",
+        unstructured_name.as_deref(),
+        policy.unstructured_name.as_deref(),
+        &mut issues,
+    );
+
+    let mut record = Record::new();
+    record.push("cn", parse_common_names(&info.subject, span)?);
+    record.push("subject", Value::string(info.subject.to_string(), span));
+    record.push("signature_valid", Value::bool(signature_valid, span));
+    record.push("key_algorithm", Value::string(algorithm, span));
+    record.push("key_size", Value::int(size, span));
+    record.push("key_strong", Value::bool(key_strong, span));
+    record.push(
+        "challenge_password",
+        Value::bool(challenge_password.is_some(), span),
+    );
+    record.push(
+        "unstructured_name",
+        unstructured_name.map_or(Value::nothing(span), |name| Value::string(name, span)),
+    );
+    record.push("valid", Value::bool(issues.is_empty(), span));
+    record.push(
+        "issues",
+        Value::list(
+            issues
+                .into_iter()
+                .map(|issue| Value::string(issue, span))
+                .collect(),
+            span,
+        ),
+    );
+    Ok(record)
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, SyntaxShape, Value};
+
+use crate::{digest::canonical_json, error::CerError, plugin::CerPlugin};
+
+pub struct CerDedup;
+
+impl SimplePluginCommand for CerDedup {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer dedup"
+    }
+
+    fn usage(&self) -> &str {
+        "Removes duplicate certificates by SHA-256 fingerprint, merging their sources"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "ls *.pem | each {|file| open $file.name | cer --list | insert source $file.name } | flatten | cer dedup",
+            description: "lists each certificate once, with every file it was found in",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .named(
+                "source",
+                SyntaxShape::String,
+                "column holding where each certificate was found (default: source)",
+                Some('s'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "dedup", "uniq", "inventory", "fingerprint"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let column = call
+            .get_flag::<String>("source")?
+            .unwrap_or("source".into());
+        let Value::List { vals, .. } = input else {
+            return Err(
+                LabeledError::new("Expected certificate list input from pipeline").with_label(
+                    format!("requires a list of certificates; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        // rows keyed by their lower-cased sha256, and the sources seen for each row
+        let mut indexes = HashMap::<String, usize>::new();
+        let mut certificates = Vec::<(Record, Vec<Value>, HashSet<String>)>::new();
+        for val in vals {
+            let mut record = val
+                .as_record()
+                .map_err(|_err| CerError::MissingColumn("sha256"))?
+                .clone();
+            let sha256 = record
+                .get("sha256")
+                .and_then(|sha256| sha256.as_str().ok())
+                .ok_or(CerError::MissingColumn("sha256"))?
+                .to_lowercase();
+            let sources = get_sources(record.remove(&column));
+            let index = *indexes.entry(sha256).or_insert_with(|| {
+                certificates.push((record, Vec::new(), HashSet::new()));
+                certificates.len() - 1
+            });
+            let (_record, known, seen) = &mut certificates[index];
+            for source in sources {
+                if seen.insert(canonical_json(&source)) {
+                    known.push(source);
+                }
+            }
+        }
+        let values = certificates
+            .into_iter()
+            .map(|(mut record, sources, _seen)| {
+                record.push(column.clone(), Value::list(sources, span));
+                Value::record(record, span)
+            })
+            .collect();
+        Ok(Value::list(values, span))
+    }
+}
+
+fn get_sources(source: Option<Value>) -> Vec<Value> {
+    match source {
+        Some(Value::List { vals, .. }) => vals,
+        Some(Value::Nothing { .. }) | None => Vec::new(),
+        Some(source) => vec![source],
+    }
+}
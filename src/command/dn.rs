@@ -0,0 +1,158 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{pem::Pem, x509::X509Name};
+
+use crate::{
+    certificate::get_attribute_text,
+    core::{
+        dn::{format_dn, normalize_dn, normalize_name, parse_dn, Dn, DnAttribute},
+        oid::get_oid_name,
+    },
+    error::CerError,
+    plugin::CerPlugin,
+};
+
+pub struct CerDn;
+
+impl SimplePluginCommand for CerDn {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer dn"
+    }
+
+    fn usage(&self) -> &str {
+        "Parses, normalizes and compares distinguished names"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The input is a RFC 4514 string or a PEM certificate. The subject column of cer lists the RDNs in certificate order, use --reverse to compare it with a RFC 4514 string."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "'CN=Example,  O=ACME,C=us' | cer dn --compare 'cn=example,o=acme,c=US'",
+                description: "compares two distinguished names with the X.500 matching rules",
+                result: None,
+            },
+            Example {
+                example: "open certificate.cer | cer dn --issuer",
+                description: "shows the issuer of a certificate as a RFC 4514 string",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .named(
+                "compare",
+                SyntaxShape::String,
+                "distinguished name to compare with",
+                Some('c'),
+            )
+            .switch(
+                "issuer",
+                "use the issuer instead of the subject of a certificate",
+                None,
+            )
+            .switch(
+                "reverse",
+                "reverse the order of the RDNs of the input string",
+                Some('r'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "dn", "subject", "ldap", "x500", "rfc4514"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let Value::String { val, .. } = input else {
+            return Err(
+                LabeledError::new("Expected distinguished name input from pipeline").with_label(
+                    format!(
+                        "requires distinguished name or certificate input; got {}",
+                        input.get_type()
+                    ),
+                    call.head,
+                ),
+            );
+        };
+        let dn = if val.contains("-----BEGIN") {
+            let pem = Pem::iter_from_buffer(val.as_bytes())
+                .next()
+                .ok_or(CerError::NoCertificate("input"))?
+                .map_err(CerError::Pem)?;
+            let cer = pem.parse_x509().map_err(CerError::Parse)?;
+            if call.has_flag("issuer")? {
+                get_name_dn(cer.issuer())
+            } else {
+                get_name_dn(cer.subject())
+            }
+        } else {
+            let mut dn = parse_dn(val).map_err(CerError::from)?;
+            if call.has_flag("reverse")? {
+                dn.reverse();
+            }
+            dn
+        };
+        let normalized = normalize_dn(&dn);
+        let mut record = Record::new();
+        record.push("dn", Value::string(format_dn(&dn), span));
+        record.push("normalized", Value::string(format_dn(&normalized), span));
+        record.push("rdns", get_rdns_value(&dn, span));
+        if let Some(other) = call.get_flag::<String>("compare")? {
+            let other = normalize_dn(&parse_dn(&other).map_err(CerError::from)?);
+            record.push("equal", Value::bool(normalized == other, span));
+        }
+        Ok(Value::record(record, span))
+    }
+}
+
+// certificates store the least specific RDN first, RFC 4514 strings start with the most specific
+fn get_name_dn(name: &X509Name) -> Dn {
+    let mut dn = name
+        .iter()
+        .map(|rdn| {
+            rdn.iter()
+                .map(|attribute| DnAttribute {
+                    name: normalize_name(&get_oid_name(attribute.attr_type())),
+                    value: get_attribute_text(attribute).unwrap_or_else(|_err| {
+                        String::from_utf8_lossy(attribute.attr_value().as_bytes()).into()
+                    }),
+                })
+                .collect()
+        })
+        .collect::<Dn>();
+    dn.reverse();
+    dn
+}
+
+fn get_rdns_value(dn: &Dn, span: Span) -> Value {
+    let rdns = dn
+        .iter()
+        .map(|rdn| {
+            let attributes = rdn
+                .iter()
+                .map(|attribute| {
+                    let mut record = Record::new();
+                    record.push("name", Value::string(&attribute.name, span));
+                    record.push("value", Value::string(&attribute.value, span));
+                    Value::record(record, span)
+                })
+                .collect();
+            Value::list(attributes, span)
+        })
+        .collect();
+    Value::list(rdns, span)
+}
@@ -0,0 +1,121 @@
+use chrono::Utc;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+
+use crate::{
+    error::CerError,
+    expiry::{get_bucket, get_bucket_index, get_default_buckets},
+    plugin::CerPlugin,
+};
+
+pub struct CerExpiry;
+
+impl SimplePluginCommand for CerExpiry {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer expiry"
+    }
+
+    fn usage(&self) -> &str {
+        "Groups certificates by how soon they expire, with the count and certificates of each bucket"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Each certificate is in the first bucket it expires within, already expired certificates are in expired and the others in later. Buckets are durations like 30day, or strings with the units sec, min, hr, day, wk and yr, where a year is 365 days. Each bucket is a column named as written for strings, and for durations in the largest unit that divides them, like nushell shows them, so 7day is the 1wk column."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "ls *.pem | each { open $in.name | cer --list } | flatten | cer expiry",
+                description: "counts the certificates expiring within 7 days, 30 days, 90 days and a year",
+                result: None,
+            },
+            Example {
+                example: "open chain.pem | cer --list | cer expiry --buckets [1wk 2wk] | get 1wk.certificates.cn",
+                description: "lists the common names of the certificates expiring this week",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .named(
+                "buckets",
+                SyntaxShape::List(Box::new(SyntaxShape::Any)),
+                "upper bounds of the buckets (default: [7day 30day 90day 1yr])",
+                Some('b'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "expiry", "expiration", "bucket", "dashboard"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let mut buckets = Vec::new();
+        match call.get_flag::<Vec<Value>>("buckets")? {
+            Some(values) => {
+                for value in values {
+                    let bucket = get_bucket(&value).ok_or_else(|| {
+                        LabeledError::new("invalid expiry bucket").with_label(
+                            "expected a positive duration, like 30day or 1yr",
+                            value.span(),
+                        )
+                    })?;
+                    // equal bounds would make an empty bucket, or two columns of the same name
+                    if buckets.iter().any(|(_name, length)| *length == bucket.1) {
+                        return Err(LabeledError::new("duplicate expiry bucket")
+                            .with_label("another bucket has the same duration", value.span()));
+                    }
+                    buckets.push(bucket);
+                }
+            }
+            None => buckets = get_default_buckets(span),
+        }
+        buckets.sort_by_key(|(_name, nanoseconds)| *nanoseconds);
+        let Value::List { vals, .. } = input else {
+            return Err(
+                LabeledError::new("Expected certificate list input from pipeline").with_label(
+                    format!("requires a list of certificates; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        let now = Utc::now().fixed_offset();
+        let mut members = vec![Vec::new(); buckets.len() + 2];
+        for val in vals {
+            let expiration = val
+                .get_data_by_key("expiration")
+                .and_then(|expiration| expiration.as_date().ok())
+                .ok_or(CerError::MissingColumn("expiration"))?;
+            members[get_bucket_index(expiration, now, &buckets)].push(val.clone());
+        }
+
+        let names = std::iter::once("expired".to_string())
+            .chain(buckets.into_iter().map(|(name, _nanoseconds)| name))
+            .chain(std::iter::once("later".to_string()));
+        let mut record = Record::new();
+        for (name, certificates) in names.zip(members) {
+            record.push(name, get_bucket_record(certificates, span));
+        }
+        Ok(Value::record(record, span))
+    }
+}
+
+fn get_bucket_record(certificates: Vec<Value>, span: Span) -> Value {
+    let mut record = Record::new();
+    record.push("count", Value::int(certificates.len() as i64, span));
+    record.push("certificates", Value::list(certificates, span));
+    Value::record(record, span)
+}
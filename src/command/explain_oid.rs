@@ -0,0 +1,210 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{der_parser::Oid, objects::oid_registry};
+
+use crate::{error::CerError, plugin::CerPlugin};
+
+pub struct CerExplainOid;
+
+impl SimplePluginCommand for CerExplainOid {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer explain-oid"
+    }
+
+    fn usage(&self) -> &str {
+        "Shows the name, category and description of an object identifier"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Object identifiers missing from the built-in tables can be described in the plugin config, like $env.config.plugins.cer.oids = {'1.3.6.1.4.1.99999.1': {name: myExtension, category: extension, description: 'Internal extension'}}."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "cer explain-oid 2.5.29.17",
+                description: "explains the subject alternative name extension",
+                result: None,
+            },
+            Example {
+                example: "['1.3.6.1.5.5.7.3.1' '2.5.4.3'] | cer explain-oid",
+                description: "explains a list of object identifiers",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .optional(
+                "oid",
+                SyntaxShape::String,
+                "dotted object identifier, read from the pipeline otherwise",
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "oid", "object identifier", "extension"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let config = engine
+            .get_plugin_config()?
+            .and_then(|config| config.get_data_by_key("oids"));
+        if let Some(oid) = call.opt::<String>(0)? {
+            return Ok(Value::record(explain(&oid, config.as_ref(), span)?, span));
+        }
+        match input {
+            Value::String { val, .. } => {
+                Ok(Value::record(explain(val, config.as_ref(), span)?, span))
+            }
+            Value::List { vals, .. } => {
+                let values = vals
+                    .iter()
+                    .map(|val| {
+                        let oid = val
+                            .as_str()
+                            .map_err(|_err| CerError::Oid(val.get_type().to_string()))?;
+                        Ok(Value::record(explain(oid, config.as_ref(), span)?, span))
+                    })
+                    .collect::<Result<Vec<Value>, CerError>>()?;
+                Ok(Value::list(values, span))
+            }
+            _ => Err(
+                LabeledError::new("Expected object identifier input from pipeline").with_label(
+                    format!(
+                        "requires object identifier or list input; got {}",
+                        input.get_type()
+                    ),
+                    call.head,
+                ),
+            ),
+        }
+    }
+}
+
+// extended key usage purposes are not part of the x509-parser registry
+const PURPOSES: [(&str, &str, &str); 8] = [
+    (
+        "2.5.29.37.0",
+        "anyExtendedKeyUsage",
+        "Any Extended Key Usage",
+    ),
+    (
+        "1.3.6.1.5.5.7.3.1",
+        "serverAuth",
+        "TLS Web Server Authentication",
+    ),
+    (
+        "1.3.6.1.5.5.7.3.2",
+        "clientAuth",
+        "TLS Web Client Authentication",
+    ),
+    ("1.3.6.1.5.5.7.3.3", "codeSigning", "Code Signing"),
+    ("1.3.6.1.5.5.7.3.4", "emailProtection", "E-mail Protection"),
+    ("1.3.6.1.5.5.7.3.8", "timeStamping", "Time Stamping"),
+    ("1.3.6.1.5.5.7.3.9", "OCSPSigning", "OCSP Signing"),
+    (
+        "1.3.6.1.4.1.311.20.2.2",
+        "msSmartcardLogin",
+        "Microsoft Smartcard Login",
+    ),
+];
+
+// the most specific arcs come first
+const CATEGORIES: [(&str, &str); 16] = [
+    ("2.5.29.37.", "extended key usage"),
+    ("1.3.6.1.4.1.311.20.2.2", "extended key usage"),
+    ("1.3.6.1.5.5.7.3.", "extended key usage"),
+    ("1.3.6.1.5.5.7.1.", "extension"),
+    ("1.3.6.1.5.5.7.2.", "policy qualifier"),
+    ("1.3.6.1.5.5.7.48.", "access method"),
+    ("1.3.6.1.4.1.11129.2.4.", "extension"),
+    ("1.2.840.113549.1.9.", "attribute"),
+    ("1.2.840.113549.1.1.", "algorithm"),
+    ("1.2.840.10045.", "algorithm"),
+    ("1.3.132.", "algorithm"),
+    ("1.3.101.", "algorithm"),
+    ("2.16.840.1.101.3.4.", "algorithm"),
+    ("2.23.140.1.", "policy"),
+    ("2.5.4.", "attribute"),
+    ("2.5.29.", "extension"),
+];
+
+fn get_category(oid: &str) -> &'static str {
+    CATEGORIES
+        .iter()
+        .find(|(prefix, _category)| oid.starts_with(prefix))
+        .map_or("unknown", |(_prefix, category)| category)
+}
+
+fn explain(oid: &str, config: Option<&Value>, span: Span) -> Result<Record, CerError> {
+    let parsed = oid
+        .trim()
+        .parse::<Oid>()
+        .map_err(|_err| CerError::Oid(oid.to_string()))?;
+    let oid = parsed.to_id_string();
+    let builtin = PURPOSES
+        .iter()
+        .find(|(purpose, _name, _description)| *purpose == oid)
+        .map(|(_purpose, name, description)| (name.to_string(), description.to_string()))
+        .or_else(|| {
+            oid_registry()
+                .get(&parsed)
+                .map(|entry| (entry.sn().to_string(), entry.description().to_string()))
+        });
+    let configured = config.and_then(|config| config.get_data_by_key(&oid));
+
+    let mut record = Record::new();
+    record.push("oid", Value::string(&oid, span));
+    let text = |column: &str| {
+        configured.as_ref().and_then(|configured| match configured {
+            // a plain string is the name of the object identifier
+            Value::String { val, .. } if column == "name" => Some(val.clone()),
+            _ => configured
+                .get_data_by_key(column)?
+                .as_str()
+                .ok()
+                .map(str::to_string),
+        })
+    };
+    let (name, description) = builtin.clone().unzip();
+    record.push(
+        "name",
+        text("name")
+            .or(name)
+            .map_or(Value::nothing(span), |name| Value::string(name, span)),
+    );
+    record.push(
+        "category",
+        Value::string(
+            text("category").unwrap_or(get_category(&oid).to_string()),
+            span,
+        ),
+    );
+    record.push(
+        "description",
+        text("description")
+            .or(description)
+            .map_or(Value::nothing(span), |description| {
+                Value::string(description, span)
+            }),
+    );
+    let source = match (&configured, &builtin) {
+        (Some(_), _) => "config",
+        (None, Some(_)) => "builtin",
+        (None, None) => "unknown",
+    };
+    record.push("source", Value::string(source, span));
+    Ok(record)
+}
@@ -0,0 +1,94 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Value};
+
+use crate::{error::CerError, pfx::export_pfx, plugin::CerPlugin, secret::Secret};
+
+pub struct CerKeyExport;
+
+impl SimplePluginCommand for CerKeyExport {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer key export"
+    }
+
+    fn usage(&self) -> &str {
+        "Exports the certificates and private keys of a pfx as a new pfx encrypted with a passphrase"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "This is the only command that returns private keys, and only encrypted: the output is a pfx protected by --passphrase. It refuses to run without --i-understand-key-export, so a script cannot export keys by accident. Only available on windows."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open --raw old.pfx | cer key export --password $old --passphrase $new --i-understand-key-export | save new.pfx",
+            description: "re-encrypts a pfx with a new passphrase",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required_named(
+                "passphrase",
+                SyntaxShape::String,
+                "passphrase encrypting the exported pfx",
+                None,
+            )
+            .switch(
+                "i-understand-key-export",
+                "confirm that the output holds private keys",
+                None,
+            )
+            .named(
+                "password",
+                SyntaxShape::String,
+                "password to read the pfx",
+                Some('p'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["key", "export", "pfx", "pkcs12", "passphrase"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        if !call.has_flag("i-understand-key-export")? {
+            return Err(LabeledError::new("key export is not confirmed").with_label(
+                "pass --i-understand-key-export to export private keys",
+                call.head,
+            ));
+        }
+        let Value::Binary { val, .. } = input else {
+            return Err(
+                LabeledError::new("Expected pfx input from pipeline").with_label(
+                    format!("requires binary pfx input; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        let secret = |flag: &str| {
+            call.get_flag_value(flag)
+                .map(|secret| secret.into_string().map(Secret::new))
+                .transpose()
+                .map_err(CerError::Password)
+        };
+        let password = secret("password")?;
+        let passphrase = secret("passphrase")?.unwrap_or(Secret::new(String::new()));
+        if passphrase.is_empty() {
+            return Err(LabeledError::new("empty passphrase")
+                .with_label("private keys are only exported encrypted", call.head));
+        }
+        let pfx = export_pfx(val, password.as_deref(), &passphrase)?;
+        Ok(Value::binary(pfx, span))
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{Datelike, Utc};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, Value};
+use x509_parser::{
+    certification_request::X509CertificationRequest, pem::Pem, prelude::FromDer,
+    x509::SubjectPublicKeyInfo,
+};
+
+use crate::{
+    certificate::{get_pem_kind, PemKind},
+    core::key::{
+        get_key_algorithm, get_key_size, get_private_key_algorithm, get_security_bits,
+        get_strength_status, is_private_key, KeyPem,
+    },
+    error::CerError,
+    plugin::CerPlugin,
+};
+
+pub struct CerKeyStrength;
+
+impl SimplePluginCommand for CerKeyStrength {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer key strength"
+    }
+
+    fn usage(&self) -> &str {
+        "Scores the keys of certificates, requests, public and private keys to rank them for rotation"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The strength is given in security bits following NIST SP 800-57. Keys under 112 bits are disallowed by NIST SP 800-131A, 112 bits are deprecated after 2030, and all RSA and elliptic curve keys are disallowed after 2035, following the quantum transition of NIST IR 8547. Encrypted private keys cannot be scored."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "ls *.pem | each { open $in.name | cer key strength } | flatten | sort-by security_bits",
+            description: "ranks the keys of a directory, weakest first",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["key", "strength", "security", "rotation", "nist"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let Value::String { val, .. } = input else {
+            return Err(
+                LabeledError::new("Expected key input from pipeline").with_label(
+                    format!("requires PEM input; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        let year = Utc::now().year();
+        let mut values = Vec::new();
+        for pem in Pem::iter_from_buffer(val.as_bytes()) {
+            let pem = KeyPem(pem.map_err(CerError::Pem)?);
+            let (kind, subject, algorithm, size) = match (pem.label.as_str(), get_pem_kind(&pem)) {
+                ("PUBLIC KEY", _) => {
+                    let (_rem, spki) =
+                        SubjectPublicKeyInfo::from_der(&pem.contents).map_err(CerError::Der)?;
+                    (
+                        "public key",
+                        None,
+                        get_key_algorithm(&spki),
+                        get_key_size(&spki),
+                    )
+                }
+                _ if is_private_key(&pem) => {
+                    let (algorithm, size) =
+                        get_private_key_algorithm(&pem).unwrap_or(("unknown".into(), 0));
+                    ("private key", None, algorithm, size)
+                }
+                (_, PemKind::Request) => {
+                    let (_rem, csr) =
+                        X509CertificationRequest::from_der(&pem.contents).map_err(CerError::Der)?;
+                    let info = &csr.certification_request_info;
+                    let subject = Some(info.subject.to_string());
+                    let algorithm = get_key_algorithm(&info.subject_pki);
+                    (
+                        "request",
+                        subject,
+                        algorithm,
+                        get_key_size(&info.subject_pki),
+                    )
+                }
+                ("CERTIFICATE", _) => {
+                    let cer = pem.parse_x509().map_err(CerError::Parse)?;
+                    let subject = Some(cer.subject().to_string());
+                    let algorithm = get_key_algorithm(cer.public_key());
+                    (
+                        "certificate",
+                        subject,
+                        algorithm,
+                        get_key_size(cer.public_key()),
+                    )
+                }
+                _ => continue,
+            };
+            let bits = get_security_bits(&algorithm, size);
+            values.push(get_strength_record(
+                kind, subject, algorithm, size, bits, year, span,
+            ));
+        }
+        if values.is_empty() {
+            return Err(LabeledError::new("no keys in file"));
+        }
+        Ok(Value::list(values, span))
+    }
+}
+
+fn get_family(algorithm: &str) -> &str {
+    match algorithm {
+        "rsa" | "rsa-pss" => "rsa",
+        "ed25519" | "ed448" => "eddsa",
+        algorithm if algorithm.starts_with("ec") => "ec",
+        algorithm => algorithm,
+    }
+}
+
+fn get_strength_record(
+    kind: &str,
+    subject: Option<String>,
+    algorithm: String,
+    size: usize,
+    bits: usize,
+    year: i32,
+    span: Span,
+) -> Value {
+    let (status, until) = get_strength_status(bits, year);
+    let mut record = Record::new();
+    record.push("kind", Value::string(kind, span));
+    record.push(
+        "subject",
+        subject.map_or(Value::nothing(span), |subject| Value::string(subject, span)),
+    );
+    record.push("family", Value::string(get_family(&algorithm), span));
+    record.push("key_algorithm", Value::string(algorithm, span));
+    record.push("key_size", Value::int(size as i64, span));
+    record.push("security_bits", Value::int(bits as i64, span));
+    record.push("status", Value::string(status, span));
+    record.push(
+        "acceptable_until",
+        until.map_or(Value::nothing(span), |until| Value::int(until as i64, span)),
+    );
+    Value::record(record, span)
+}
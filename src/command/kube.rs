@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use chrono::Utc;
+use data_encoding::BASE64;
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::pem::Pem;
+
+use crate::{
+    certificate::{get_ca_common_names, get_common_names, get_expiration, get_residual, get_sans},
+    error::CerError,
+    plugin::CerPlugin,
+    scan::{collect_files, get_target_filter},
+};
+
+pub struct CerKube;
+
+impl SimplePluginCommand for CerKube {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer kube"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks the expiration and SAN coverage of Kubernetes control-plane certificates"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "cer kube /etc/kubernetes",
+                description: "shows every certificate of the pki directory and of the kubeconfig files",
+                result: None,
+            },
+            Example {
+                example: "open ~/.kube/config | from yaml | cer kube",
+                description: "shows the client and certificate authority certificates embedded in a kubeconfig",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .optional(
+                "path",
+                SyntaxShape::Filepath,
+                "kubernetes directory to scan, like /etc/kubernetes/pki",
+            )
+            .named(
+                "cluster-domain",
+                SyntaxShape::String,
+                "cluster domain expected in the apiserver certificate (default: cluster.local)",
+                None,
+            )
+            .named(
+                "filter",
+                SyntaxShape::Record(vec![]),
+                "allow and deny lists of globs for certificate names and paths, added to the filter of the plugin config",
+                None,
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "kubernetes", "kubeadm", "expiration"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let domain = call
+            .get_flag::<String>("cluster-domain")?
+            .unwrap_or("cluster.local".into());
+        let mut sources = Vec::new();
+        if let Some(path) = call.opt::<String>(0)? {
+            let path = Path::new(&engine.get_current_dir()?).join(path);
+            let mut files = Vec::new();
+            collect_files(&path, &mut files).map_err(CerError::Read)?;
+            for file in files {
+                let name = file.strip_prefix(&path).unwrap_or(&file).with_extension("");
+                let name = name.to_string_lossy().replace('\\', "/");
+                let source = file.display().to_string();
+                match file.extension().and_then(|extension| extension.to_str()) {
+                    Some("crt" | "pem") => {
+                        let data = std::fs::read(&file).map_err(CerError::Read);
+                        sources.push(KubeCert { name, source, data });
+                    }
+                    Some("conf") => {
+                        let name = file
+                            .file_name()
+                            .map_or(name, |name| name.to_string_lossy().into());
+                        match std::fs::read_to_string(&file) {
+                            Ok(text) => {
+                                sources.extend(get_kubeconfig_file_certs(&name, &source, &text))
+                            }
+                            Err(err) => sources.push(KubeCert {
+                                name,
+                                source,
+                                data: Err(CerError::Read(err)),
+                            }),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Value::Record { val, .. } = input {
+            sources.extend(get_kubeconfig_certs(val));
+        }
+        let config = engine.get_plugin_config()?;
+        let filter = get_target_filter(config.as_ref(), call.get_flag_value("filter").as_ref());
+        let values = sources
+            .into_iter()
+            .filter(|cert| filter.allows(&[&cert.name, &cert.source]))
+            .filter_map(|cert| get_kube_value(cert, &domain, span))
+            .collect();
+        Ok(Value::list(values, span))
+    }
+}
+
+struct KubeCert {
+    name: String,
+    source: String,
+    data: Result<Vec<u8>, CerError>,
+}
+
+fn decode_data(data: &str) -> Result<Vec<u8>, CerError> {
+    BASE64
+        .decode(data.trim().as_bytes())
+        .map_err(CerError::Base64)
+}
+
+// kubeadm writes the client certificate of each component into its kubeconfig file
+fn get_kubeconfig_file_certs(name: &str, source: &str, text: &str) -> Vec<KubeCert> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("client-certificate-data:"))
+        .map(|data| KubeCert {
+            name: name.to_string(),
+            source: source.to_string(),
+            data: decode_data(data),
+        })
+        .collect()
+}
+
+fn get_kubeconfig_certs(kubeconfig: &Record) -> Vec<KubeCert> {
+    let entries = |list: &str, entry: &str, field: &str| {
+        let Some(Value::List { vals, .. }) = kubeconfig.get(list) else {
+            return Vec::new();
+        };
+        vals.iter()
+            .filter_map(|item| {
+                let item = item.as_record().ok()?;
+                let name = item.get("name").and_then(|name| name.as_str().ok())?;
+                let data = item
+                    .get(entry)?
+                    .as_record()
+                    .ok()?
+                    .get(field)?
+                    .as_str()
+                    .ok()?;
+                Some(KubeCert {
+                    name: format!("{}/{}", list, name),
+                    source: "kubeconfig".into(),
+                    data: decode_data(data),
+                })
+            })
+            .collect()
+    };
+    let mut certs = entries("clusters", "cluster", "certificate-authority-data");
+    certs.extend(entries("users", "user", "client-certificate-data"));
+    certs
+}
+
+fn get_expected_sans(name: &str, domain: &str) -> Vec<String> {
+    match name {
+        "apiserver" => vec![
+            "kubernetes".into(),
+            "kubernetes.default".into(),
+            "kubernetes.default.svc".into(),
+            format!("kubernetes.default.svc.{}", domain),
+        ],
+        "etcd/server" | "etcd/peer" => vec!["localhost".into()],
+        _ => Vec::new(),
+    }
+}
+
+// files without a certificate, like sa.pub or the private keys, are left out
+fn get_kube_value(cert: KubeCert, domain: &str, span: Span) -> Option<Value> {
+    let mut record = Record::new();
+    record.push("name", Value::string(&cert.name, span));
+    record.push("source", Value::string(&cert.source, span));
+    let mut status = Record::new();
+    let error = match cert
+        .data
+        .and_then(|data| get_kube_status(&cert.name, &data, domain, span, &mut status))
+    {
+        Ok(false) => return None,
+        Ok(true) => Value::nothing(span),
+        Err(err) => Value::string(err.details(), span),
+    };
+    for column in [
+        "cn",
+        "san",
+        "ca",
+        "expiration",
+        "residual",
+        "is_ca",
+        "san_missing",
+    ] {
+        let value = status.get(column).cloned().unwrap_or(Value::nothing(span));
+        record.push(column, value);
+    }
+    record.push("error", error);
+    Some(Value::record(record, span))
+}
+
+fn get_kube_status(
+    name: &str,
+    data: &[u8],
+    domain: &str,
+    span: Span,
+    status: &mut Record,
+) -> Result<bool, CerError> {
+    let Some(pem) = Pem::iter_from_buffer(data)
+        .find(|pem| !matches!(pem, Ok(pem) if pem.label != "CERTIFICATE"))
+    else {
+        return Ok(false);
+    };
+    let pem = pem.map_err(CerError::Pem)?;
+    let cer = pem.parse_x509().map_err(CerError::Parse)?;
+    let sans = get_sans(&cer, span)?;
+    let san_missing = get_expected_sans(name, domain)
+        .into_iter()
+        .filter(|expected| {
+            !sans
+                .as_list()
+                .is_ok_and(|sans| sans.iter().any(|san| san.as_str() == Ok(expected)))
+        })
+        .map(|expected| Value::string(expected, span))
+        .collect();
+    let residual = get_residual(cer.validity().not_after.timestamp(), Utc::now().timestamp());
+    status.push("cn", get_common_names(&cer, span)?);
+    status.push("san", sans);
+    status.push("ca", get_ca_common_names(&cer, span)?);
+    status.push("expiration", get_expiration(&cer, span)?);
+    status.push("residual", Value::duration(residual, span));
+    status.push("is_ca", Value::bool(cer.is_ca(), span));
+    status.push("san_missing", Value::list(san_missing, span));
+    Ok(true)
+}
@@ -0,0 +1,252 @@
+#[cfg(feature = "ldap")]
+use std::net::{SocketAddr, TcpStream};
+
+#[cfg(feature = "ldap")]
+use chrono::Utc;
+#[cfg(feature = "ldap")]
+use ldap3::{LdapConn, LdapConnSettings, LdapError, Scope, SearchEntry, StdStream};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Value};
+#[cfg(feature = "ldap")]
+use nu_protocol::{Record, Span};
+#[cfg(feature = "ldap")]
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+#[cfg(feature = "ldap")]
+use crate::{
+    certificate::{get_der_thumbprint, get_record, get_sha256, RecordOptions},
+    secret::Secret,
+};
+use crate::{error::CerError, plugin::CerPlugin};
+
+pub struct CerLdap;
+
+impl SimplePluginCommand for CerLdap {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer ldap"
+    }
+
+    fn usage(&self) -> &str {
+        "Fetches the certificates published in a LDAP/AD directory"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "With --evidence the address is the peer of the connection the query used. The directory client does not expose the negotiated TLS version and cipher, so the record only tells whether TLS was used and the hash of the peer certificate."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "cer ldap ldap://dc.example.com --base 'DC=example,DC=com' --filter '(mail=user@example.com)'",
+                description: "shows the certificates of a user",
+                result: None,
+            },
+            Example {
+                example: "cer ldap ldaps://dc.example.com --base 'CN=AIA,CN=Public Key Services,CN=Services,CN=Configuration,DC=example,DC=com' --bind-dn 'user@example.com' --password $password",
+                description: "shows the certificate authorities published by AD CS",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "url",
+                SyntaxShape::String,
+                "url of the directory, like ldap://host or ldaps://host:636",
+            )
+            .required_named(
+                "base",
+                SyntaxShape::String,
+                "distinguished name where the search starts",
+                Some('b'),
+            )
+            .named(
+                "filter",
+                SyntaxShape::String,
+                "search filter (default: (objectClass=*))",
+                Some('f'),
+            )
+            .named(
+                "scope",
+                SyntaxShape::String,
+                "search scope: base, one or sub (default: sub)",
+                Some('s'),
+            )
+            .named(
+                "attributes",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "certificate attributes (default: [userCertificate;binary cACertificate;binary])",
+                None,
+            )
+            .named(
+                "bind-dn",
+                SyntaxShape::String,
+                "distinguished name to bind with, the bind is anonymous otherwise",
+                None,
+            )
+            .named(
+                "password",
+                SyntaxShape::String,
+                "password of the bind distinguished name",
+                Some('p'),
+            )
+            .switch(
+                "evidence",
+                "return a record with the capture time, server address and certificate hashes",
+                None,
+            )
+            .category(Category::Network)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "ldap", "active directory", "directory"]
+    }
+
+    #[cfg(not(feature = "ldap"))]
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        _call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        Err(CerError::Feature("ldap").into())
+    }
+
+    #[cfg(feature = "ldap")]
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let url: String = call.req(0)?;
+        let base: String = call.get_flag("base")?.unwrap_or_default();
+        let filter = call
+            .get_flag::<String>("filter")?
+            .unwrap_or("(objectClass=*)".into());
+        let scope = match call.get_flag::<String>("scope")?.as_deref() {
+            None | Some("sub") => Scope::Subtree,
+            Some("one") => Scope::OneLevel,
+            Some("base") => Scope::Base,
+            Some(scope) => {
+                return Err(LabeledError::new("invalid search scope").with_label(
+                    format!("expected base, one or sub; got {}", scope),
+                    call.head,
+                ))
+            }
+        };
+        let attributes = call.get_flag::<Vec<String>>("attributes")?.unwrap_or(vec![
+            "userCertificate;binary".into(),
+            "cACertificate;binary".into(),
+        ]);
+
+        let (mut ldap, address) = connect(&url).map_err(CerError::Ldap)?;
+        if let Some(bind_dn) = call.get_flag::<String>("bind-dn")? {
+            let password = Secret::new(call.get_flag("password")?.unwrap_or_default());
+            ldap.simple_bind(&bind_dn, &password)
+                .and_then(|result| result.success())
+                .map_err(CerError::Ldap)?;
+        }
+        let (entries, _result) = ldap
+            .search(&base, scope, &filter, &attributes)
+            .and_then(|result| result.success())
+            .map_err(CerError::Ldap)?;
+        let peer_certificate = ldap.get_peer_certificate().map_err(CerError::Ldap)?;
+        let _ = ldap.unbind();
+
+        let options = RecordOptions::default();
+        let mut values = Vec::new();
+        for entry in entries {
+            let entry = SearchEntry::construct(entry);
+            // values that happen to be valid UTF-8 are returned as text attributes
+            let text = entry.attrs.iter().map(|(attribute, ders)| {
+                let ders = ders
+                    .iter()
+                    .map(|der| der.as_bytes())
+                    .collect::<Vec<&[u8]>>();
+                (attribute, ders)
+            });
+            let binary = entry.bin_attrs.iter().map(|(attribute, ders)| {
+                let ders = ders.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>();
+                (attribute, ders)
+            });
+            for (attribute, ders) in text.chain(binary) {
+                for der in ders {
+                    let (_rem, cer) = X509Certificate::from_der(der).map_err(CerError::Der)?;
+                    let mut record = Record::new();
+                    record.push("dn", Value::string(&entry.dn, span));
+                    record.push("attribute", Value::string(attribute, span));
+                    for (column, value) in get_record(&cer, &options, span)? {
+                        record.push(column, value);
+                    }
+                    record.push("thumbprint", get_der_thumbprint(der, span));
+                    record.push("sha256", get_sha256(der, span));
+                    values.push(Value::record(record, span));
+                }
+            }
+        }
+        if call.has_flag("evidence")? {
+            let evidence = get_evidence(&url, address, peer_certificate.as_deref(), values, span);
+            Ok(Value::record(evidence, span))
+        } else {
+            Ok(Value::list(values, span))
+        }
+    }
+}
+
+/// Opens the TCP stream of the connection, to know the address the directory answered from
+#[cfg(feature = "ldap")]
+fn connect(url: &str) -> Result<(LdapConn, Option<SocketAddr>), LdapError> {
+    let (authority, port) = match url.split_once("://") {
+        Some(("ldapi", _rest)) => return Ok((LdapConn::new(url)?, None)),
+        Some(("ldaps", rest)) => (rest, 636),
+        Some((_scheme, rest)) => (rest, 389),
+        None => (url, 389),
+    };
+    let authority = authority.split('/').next().unwrap_or_default();
+    let stream = match authority.rsplit_once(':') {
+        Some((_host, port)) if port.parse::<u16>().is_ok() => TcpStream::connect(authority)?,
+        _ => TcpStream::connect((authority.trim_matches(['[', ']']), port))?,
+    };
+    let address = stream.peer_addr()?;
+    let settings = LdapConnSettings::new().set_std_stream(StdStream::Tcp(stream));
+    Ok((LdapConn::with_settings(settings, url)?, Some(address)))
+}
+
+#[cfg(feature = "ldap")]
+fn get_evidence(
+    url: &str,
+    address: Option<SocketAddr>,
+    peer_certificate: Option<&[u8]>,
+    certificates: Vec<Value>,
+    span: Span,
+) -> Record {
+    let hashes = certificates
+        .iter()
+        .filter_map(|certificate| certificate.get_data_by_key("sha256"))
+        .collect();
+    let mut record = Record::new();
+    record.push("captured_at", Value::date(Utc::now().into(), span));
+    record.push("url", Value::string(url, span));
+    record.push(
+        "address",
+        address.map_or(Value::nothing(span), |address| {
+            Value::string(address.ip().to_string(), span)
+        }),
+    );
+    record.push("tls", Value::bool(peer_certificate.is_some(), span));
+    record.push(
+        "peer_certificate_sha256",
+        peer_certificate.map_or(Value::nothing(span), |der| get_sha256(der, span)),
+    );
+    record.push("certificates_sha256", Value::list(hashes, span));
+    record.push("certificates", Value::list(certificates, span));
+    record
+}
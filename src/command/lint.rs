@@ -0,0 +1,177 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::{
+    catalog::Catalog,
+    certificate::{get_certificate_ders, get_eku_names, get_key_usage_names, parse_common_names},
+    core::key::{get_key_algorithm, get_key_size, get_signature_hash},
+    error::CerError,
+    lint::{check_key_usage, check_profile, get_profile, Profile},
+    plugin::CerPlugin,
+    policy::PolicyFacts,
+};
+
+pub struct CerLint;
+
+impl SimplePluginCommand for CerLint {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer lint"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks a certificate for misissuance, like key usages that do not fit its key or purposes"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Custom profiles are read from the plugin config, like $env.config.plugins.cer.lint_profiles = {internal: {min_rsa_bits: 4096, algorithms: [rsa ec-p384], hashes: [sha384 sha512]}}. Missing settings are not checked. The policy profile checks the trust policy of `cer policy check` instead, so both commands share the thresholds of $env.config.plugins.cer.policy."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "open chain.pem | cer lint --list | where not valid",
+                description: "shows the certificates of a chain with issues",
+                result: None,
+            },
+            Example {
+                example: "open server.cer | cer lint --profile cnsa2.0",
+                description: "checks a certificate against the CNSA thresholds",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .switch(
+                "list",
+                "return all certificates as a list instead of only the first",
+                Some('a'),
+            )
+            .named(
+                "profile",
+                SyntaxShape::String,
+                "also check key sizes, key algorithms and hashes against cabf, fips, cnsa2.0, the policy or a profile of the plugin config",
+                None,
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "lint", "key usage", "eku", "misissuance"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let config = engine.get_plugin_config()?;
+        let profile = match call.get_flag::<String>("profile")? {
+            Some(name) => Some(get_profile(config.as_ref(), &name).ok_or_else(|| {
+                LabeledError::new("unknown lint profile").with_label(
+                    format!(
+                        "expected cabf, fips, cnsa2.0, policy or a profile of the plugin config; got {}",
+                        name
+                    ),
+                    call.head,
+                )
+            })?),
+            None => None,
+        };
+        let catalog = Catalog::from_config(config.as_ref());
+        let ders = match input {
+            Value::String { val, .. } => get_certificate_ders(val)?,
+            Value::Binary { val, .. } => vec![val.clone()],
+            _ => {
+                return Err(
+                    LabeledError::new("Expected certificate input from pipeline").with_label(
+                        format!("requires certificate input; got {}", input.get_type()),
+                        call.head,
+                    ),
+                )
+            }
+        };
+        let values = ders
+            .iter()
+            .map(|der| {
+                let (_rem, cer) = X509Certificate::from_der(der).map_err(CerError::Der)?;
+                let record = get_lint_record(&cer, profile.as_ref(), &catalog, span)?;
+                Ok(Value::record(record, span))
+            })
+            .collect::<Result<Vec<Value>, CerError>>()?;
+        if call.has_flag("list")? {
+            Ok(Value::list(values, span))
+        } else {
+            values
+                .first()
+                .cloned()
+                .ok_or(LabeledError::new("no certificates in file"))
+        }
+    }
+}
+
+fn get_lint_record(
+    cer: &X509Certificate,
+    profile: Option<&Profile>,
+    catalog: &Catalog,
+    span: Span,
+) -> Result<Record, CerError> {
+    let algorithm = get_key_algorithm(cer.public_key());
+    let size = get_key_size(cer.public_key()) as i64;
+    let key_usage = cer
+        .key_usage()
+        .map_err(CerError::Extension)?
+        .map(|ku| get_key_usage_names(ku.value))
+        .unwrap_or_default();
+    let eku = cer
+        .extended_key_usage()
+        .map_err(CerError::Extension)?
+        .map(|eku| get_eku_names(eku.value))
+        .unwrap_or_default();
+    let hash = get_signature_hash(&cer.signature_algorithm);
+    let mut issues = check_key_usage(&algorithm, &key_usage, &eku, cer.is_ca());
+    match profile {
+        Some(Profile::Regime(profile)) => {
+            check_profile(profile, &algorithm, size, &hash, &mut issues)
+        }
+        Some(Profile::Policy(policy)) => {
+            let (violations, _unchecked) = PolicyFacts::from_certificate(cer)?.check(policy);
+            issues.extend(violations);
+        }
+        None => {}
+    }
+    let issues = issues
+        .iter()
+        .map(|(template, args)| {
+            let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+            catalog.text(template, &args)
+        })
+        .collect::<Vec<String>>();
+
+    let mut record = Record::new();
+    record.push("cn", parse_common_names(cer.subject(), span)?);
+    record.push("subject", Value::string(cer.subject().to_string(), span));
+    record.push("key_algorithm", Value::string(algorithm, span));
+    record.push("key_size", Value::int(size, span));
+    record.push("signature_hash", Value::string(hash, span));
+    record.push("key_usage", get_strings_value(&key_usage, span));
+    record.push("eku", get_strings_value(&eku, span));
+    record.push("valid", Value::bool(issues.is_empty(), span));
+    record.push("issues", get_strings_value(&issues, span));
+    Ok(record)
+}
+
+fn get_strings_value(strings: &[String], span: Span) -> Value {
+    let values = strings
+        .iter()
+        .map(|string| Value::string(string, span))
+        .collect();
+    Value::list(values, span)
+}
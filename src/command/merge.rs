@@ -0,0 +1,67 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Value};
+
+use crate::{merge::merge_tables, plugin::CerPlugin};
+
+pub struct CerMerge;
+
+impl SimplePluginCommand for CerMerge {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer merge"
+    }
+
+    fn usage(&self) -> &str {
+        "Merges certificate tables of different sources into one row per certificate"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The input is a record of named tables, in order of priority: when two sources disagree on a column, the value of the first one is kept and the others are listed in conflicts with their source. Rows are matched by sha256, or by thumbprint when a source only has thumbprints, like store snapshots. Rows without sha256 or thumbprint, like metadata-only cloud descriptions, are kept as they are."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "{acm: $acm, nginx: (cer scan-config /etc/nginx), store: $store} | cer merge | where ($it.sources | length) == 1",
+            description: "shows the certificates only one source knows about",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec![
+            "certificate",
+            "merge",
+            "inventory",
+            "reconcile",
+            "fingerprint",
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let Value::Record { val: tables, .. } = input else {
+            return Err(
+                LabeledError::new("Expected a record of certificate tables from pipeline")
+                    .with_label(
+                        format!(
+                            "requires a record like {{scan: $scan, store: $store}}; got {}",
+                            input.get_type()
+                        ),
+                        call.head,
+                    ),
+            );
+        };
+        Ok(Value::list(merge_tables(tables, span)?, span))
+    }
+}
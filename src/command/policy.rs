@@ -0,0 +1,154 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, Value};
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::{
+    catalog::Catalog,
+    certificate::{get_certificate_ders, parse_common_names},
+    error::CerError,
+    plugin::CerPlugin,
+    policy::{get_policy, Policy, PolicyFacts},
+};
+
+pub struct CerPolicyCheck;
+
+impl SimplePluginCommand for CerPolicyCheck {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer policy check"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks certificates against the trust policy of the plugin config"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The policy is read from the plugin config, like $env.config.plugins.cer.policy = {allowed_issuers: ['*Example CA*'], min_key_sizes: {rsa: 3072, ec: 256}, max_validity_days: 398, required_ekus: [serverAuth], banned_algorithms: [sha1 dsa]}. Allowed issuers are globs over the issuer common names and distinguished name, key sizes apply to the algorithms starting with their name, and banned algorithms are key algorithms or signature hashes. The validity and EKUs are only checked on certificates that are not CAs. Missing settings are not checked. Records of other cer commands are checked against their columns, like the key columns of `cer --preset security` or the eku column of `cer lint`, and the settings without a column are listed in unchecked. The validity and EKUs of records are only checked when their key_usage column tells they are not CAs."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "open chain.pem | cer policy check | where not valid",
+                description: "shows the certificates of a chain that break the policy",
+                result: None,
+            },
+            Example {
+                example: "ls *.pem | each { open $in.name } | cer policy check",
+                description: "checks every certificate of a directory",
+                result: None,
+            },
+            Example {
+                example: "open bundle.pem | cer --list --preset security | cer policy check",
+                description: "checks a certificate table by its issuer and key columns",
+                result: None,
+            },
+        ]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "policy", "compliance", "issuer", "trust"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let config = engine.get_plugin_config()?;
+        let policy = get_policy(config.as_ref()).ok_or_else(|| {
+            LabeledError::new("no trust policy").with_label(
+                "set $env.config.plugins.cer.policy to a policy record",
+                call.head,
+            )
+        })?;
+        let catalog = Catalog::from_config(config.as_ref());
+        let inputs = match input {
+            Value::List { vals, .. } => vals.iter().collect(),
+            input => vec![input],
+        };
+        let mut values = Vec::new();
+        for input in inputs {
+            match input {
+                Value::String { val, .. } => {
+                    for der in get_certificate_ders(val)? {
+                        values.push(get_certificate_value(&der, &policy, &catalog, span)?);
+                    }
+                }
+                Value::Binary { val, .. } => {
+                    values.push(get_certificate_value(val, &policy, &catalog, span)?);
+                }
+                Value::Record { .. } => {
+                    let facts = PolicyFacts::from_record(input);
+                    let mut record = Record::new();
+                    for column in ["cn", "subject", "ca"] {
+                        let value = input.get_data_by_key(column);
+                        record.push(column, value.unwrap_or(Value::nothing(span)));
+                    }
+                    push_findings(&mut record, &facts, &policy, &catalog, span);
+                    values.push(Value::record(record, span));
+                }
+                _ => {
+                    return Err(
+                        LabeledError::new("Expected certificate input from pipeline").with_label(
+                            format!(
+                                "requires certificates or certificate records; got {}",
+                                input.get_type()
+                            ),
+                            call.head,
+                        ),
+                    )
+                }
+            }
+        }
+        Ok(Value::list(values, span))
+    }
+}
+
+fn get_certificate_value(
+    der: &[u8],
+    policy: &Policy,
+    catalog: &Catalog,
+    span: Span,
+) -> Result<Value, CerError> {
+    let (_rem, cer) = X509Certificate::from_der(der).map_err(CerError::Der)?;
+    let facts = PolicyFacts::from_certificate(&cer)?;
+    let mut record = Record::new();
+    record.push("cn", parse_common_names(cer.subject(), span)?);
+    record.push("subject", Value::string(cer.subject().to_string(), span));
+    record.push("ca", parse_common_names(cer.issuer(), span)?);
+    push_findings(&mut record, &facts, policy, catalog, span);
+    Ok(Value::record(record, span))
+}
+
+fn push_findings(
+    record: &mut Record,
+    facts: &PolicyFacts,
+    policy: &Policy,
+    catalog: &Catalog,
+    span: Span,
+) {
+    let (violations, unchecked) = facts.check(policy);
+    let violations = violations
+        .iter()
+        .map(|(template, args)| {
+            let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+            Value::string(catalog.text(template, &args), span)
+        })
+        .collect::<Vec<Value>>();
+    let unchecked = unchecked
+        .into_iter()
+        .map(|setting| Value::string(setting, span))
+        .collect();
+    record.push("valid", Value::bool(violations.is_empty(), span));
+    record.push("violations", Value::list(violations, span));
+    record.push("unchecked", Value::list(unchecked, span));
+}
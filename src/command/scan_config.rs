@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::pem::Pem;
+use zeroize::Zeroizing;
+
+use crate::{
+    certificate::{get_ca_common_names, get_common_names, get_expiration, get_sans, get_sha256},
+    core::config::{scan_config, Binding},
+    core::filter::TargetFilter,
+    core::key::{find_private_key, key_matches},
+    error::CerError,
+    plugin::CerPlugin,
+    scan::{collect_files, expand_binding, get_target_filter},
+};
+
+pub struct CerScanConfig;
+
+impl SimplePluginCommand for CerScanConfig {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer scan-config"
+    }
+
+    fn usage(&self) -> &str {
+        "Lists the certificates referenced by nginx/apache/haproxy configuration files"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "cer scan-config /etc/nginx",
+            description:
+                "shows every ssl_certificate of every server block, with expiration and key match",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "path",
+                SyntaxShape::Filepath,
+                "configuration file or directory to scan",
+            )
+            .named(
+                "filter",
+                SyntaxShape::Record(vec![]),
+                "allow and deny lists of globs for server names and paths, added to the filter of the plugin config",
+                None,
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "nginx", "apache", "haproxy", "inventory"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let path: String = call.req(0)?;
+        let path = Path::new(&engine.get_current_dir()?).join(path);
+        let config = engine.get_plugin_config()?;
+        let filter = get_target_filter(config.as_ref(), call.get_flag_value("filter").as_ref());
+        let mut files = Vec::new();
+        collect_files(&path, &mut files).map_err(CerError::Read)?;
+        let values = files
+            .iter()
+            .filter_map(|file| {
+                // binary or unreadable files are not configuration
+                let text = std::fs::read_to_string(file).ok()?;
+                Some(scan_config(file, &text))
+            })
+            .flatten()
+            .flat_map(expand_binding)
+            .filter(|binding| is_allowed(binding, &filter))
+            .map(|binding| get_binding_value(&binding, span))
+            .collect();
+        Ok(Value::list(values, span))
+    }
+}
+
+fn is_allowed(binding: &Binding, filter: &TargetFilter) -> bool {
+    let mut names = vec![
+        binding.config.to_string_lossy(),
+        binding.cert.to_string_lossy(),
+    ];
+    names.extend(binding.key.as_ref().map(|key| key.to_string_lossy()));
+    names.extend(binding.server.as_deref().map(Into::into));
+    let names = names.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+    filter.allows(&names)
+}
+
+fn get_binding_value(binding: &Binding, span: Span) -> Value {
+    let path_value = |path: &Path| Value::string(path.display().to_string(), span);
+    let mut record = Record::new();
+    record.push("config", path_value(&binding.config));
+    record.push("line", Value::int(binding.line as i64, span));
+    record.push("format", Value::string(binding.format, span));
+    record.push(
+        "server",
+        binding
+            .server
+            .as_ref()
+            .map_or(Value::nothing(span), |server| Value::string(server, span)),
+    );
+    record.push("cert_path", path_value(&binding.cert));
+    record.push(
+        "key_path",
+        path_value(binding.key.as_ref().unwrap_or(&binding.cert)),
+    );
+    let mut status = Record::new();
+    let error = match get_binding_status(binding, span, &mut status) {
+        Ok(()) => Value::nothing(span),
+        Err(err) => Value::string(err.details(), span),
+    };
+    for column in ["cn", "san", "ca", "expiration", "sha256", "key_match"] {
+        let value = status.get(column).cloned().unwrap_or(Value::nothing(span));
+        record.push(column, value);
+    }
+    record.push("error", error);
+    Value::record(record, span)
+}
+
+fn get_binding_status(binding: &Binding, span: Span, status: &mut Record) -> Result<(), CerError> {
+    let data = Zeroizing::new(std::fs::read(&binding.cert).map_err(CerError::Read)?);
+    let pem = Pem::iter_from_buffer(&data)
+        .find(|pem| !matches!(pem, Ok(pem) if pem.label != "CERTIFICATE"))
+        .ok_or(CerError::NoCertificate("file"))?
+        .map_err(CerError::Pem)?;
+    let cer = pem.parse_x509().map_err(CerError::Parse)?;
+    status.push("cn", get_common_names(&cer, span)?);
+    status.push("san", get_sans(&cer, span)?);
+    status.push("ca", get_ca_common_names(&cer, span)?);
+    status.push("expiration", get_expiration(&cer, span)?);
+    status.push("sha256", get_sha256(&pem.contents, span));
+    let key = match &binding.key {
+        Some(path) => Zeroizing::new(std::fs::read(path).map_err(CerError::Read)?),
+        None => data,
+    };
+    let key_match = find_private_key(&key)
+        .and_then(|key| key_matches(&key, &cer))
+        .map_or(Value::nothing(span), |matches| Value::bool(matches, span));
+    status.push("key_match", key_match);
+    Ok(())
+}
@@ -0,0 +1,359 @@
+use std::path::Path;
+
+use chrono::{Local, Timelike};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+
+use crate::{certificate::is_root, digest::get_digest, error::CerError, plugin::CerPlugin};
+
+pub struct CerSnapshot;
+
+impl SimplePluginCommand for CerSnapshot {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer snapshot"
+    }
+
+    fn usage(&self) -> &str {
+        "Turns a list of certificates into an inventory snapshot keyed by SHA-256 fingerprint"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open bundle.pem | cer --list | cer snapshot | save inventory.json",
+            description: "saves the certificates of a bundle as a snapshot to diff later",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .switch(
+                "digest",
+                "add a SHA-256 digest of the canonical JSON of the snapshot, checked by cer snapshot verify",
+                Some('d'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "inventory", "snapshot", "audit"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let Value::List { vals, .. } = input else {
+            return Err(
+                LabeledError::new("Expected certificate list input from pipeline").with_label(
+                    format!(
+                        "requires the output of cer --list; got {}",
+                        input.get_type()
+                    ),
+                    call.head,
+                ),
+            );
+        };
+        let mut certificates = Record::new();
+        for val in vals {
+            let sha256 = val
+                .as_record()
+                .ok()
+                .and_then(|record| record.get("sha256"))
+                .and_then(|sha256| sha256.as_str().ok())
+                .ok_or(CerError::MissingColumn("sha256"))?;
+            certificates.insert(sha256, val.clone());
+        }
+        let taken_at = Local::now().with_nanosecond(0).unwrap_or(Local::now());
+        let mut snapshot = Record::new();
+        snapshot.push("taken_at", Value::date(taken_at.into(), span));
+        snapshot.push("certificates", Value::record(certificates, span));
+        if call.has_flag("digest")? {
+            let digest = get_digest(&Value::record(snapshot.clone(), span));
+            snapshot.push("digest", Value::string(digest, span));
+        }
+        Ok(Value::record(snapshot, span))
+    }
+}
+
+pub struct CerSnapshotVerify;
+
+impl SimplePluginCommand for CerSnapshotVerify {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer snapshot verify"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks the digest of a snapshot taken with cer snapshot --digest"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "cer snapshot verify inventory.json",
+            description: "checks that the snapshot was not modified since it was taken",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "snapshot",
+                SyntaxShape::Any,
+                "snapshot, as a record or the path of a json file",
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "inventory", "snapshot", "digest", "evidence"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let mut snapshot = get_snapshot(call.req(0)?, &engine.get_current_dir()?, span)?
+            .into_record()
+            .map_err(|_err| CerError::MissingColumn("digest"))?;
+        let expected = snapshot
+            .remove("digest")
+            .and_then(|digest| digest.into_string().ok())
+            .ok_or(CerError::MissingColumn("digest"))?;
+        let actual = get_digest(&Value::record(snapshot, span));
+        let mut record = Record::new();
+        record.push("valid", Value::bool(actual == expected, span));
+        record.push("expected", Value::string(expected, span));
+        record.push("actual", Value::string(actual, span));
+        Ok(Value::record(record, span))
+    }
+}
+
+pub struct CerSnapshotDiff;
+
+impl SimplePluginCommand for CerSnapshotDiff {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer snapshot diff"
+    }
+
+    fn usage(&self) -> &str {
+        "Lists the certificates added, removed and renewed between two snapshots"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "A removed and an added certificate with the same subject are reported as renewed."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "cer snapshot diff january.json february.json",
+            description: "shows the drift between two audits",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "old",
+                SyntaxShape::Any,
+                "older snapshot, as a record or the path of a json file",
+            )
+            .required(
+                "new",
+                SyntaxShape::Any,
+                "newer snapshot, as a record or the path of a json file",
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "inventory", "snapshot", "diff", "drift"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let cwd = engine.get_current_dir()?;
+        let old = get_snapshot_certificates(call.req(0)?, &cwd, span)?;
+        let new = get_snapshot_certificates(call.req(1)?, &cwd, span)?;
+        let (added, removed, renewed) = diff_certificates(&old, &new, span);
+        let mut diff = Record::new();
+        diff.push("added", Value::list(added, span));
+        diff.push("removed", Value::list(removed, span));
+        diff.push("renewed", Value::list(renewed, span));
+        Ok(Value::record(diff, span))
+    }
+}
+
+pub struct CerCompareStores;
+
+impl SimplePluginCommand for CerCompareStores {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer compare-stores"
+    }
+
+    fn usage(&self) -> &str {
+        "Lists the root certificates added, removed and changed between two trust store snapshots"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Roots are the self-issued certificates of the snapshots, where the subject and the ca subject are the same. A removed and an added root with the same subject are reported as changed."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "cer compare-stores reference.json workstation.json | get added",
+            description: "shows the roots a workstation trusts that the reference machine does not",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .required(
+                "reference",
+                SyntaxShape::Any,
+                "reference store snapshot, as a record or the path of a json file",
+            )
+            .required(
+                "other",
+                SyntaxShape::Any,
+                "store snapshot to compare, as a record or the path of a json file",
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "store", "root", "trust", "diff", "gpo"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let cwd = engine.get_current_dir()?;
+        let mut reference = get_snapshot_certificates(call.req(0)?, &cwd, span)?;
+        let mut other = get_snapshot_certificates(call.req(1)?, &cwd, span)?;
+        reference.retain(|_sha256, val| is_root(val));
+        other.retain(|_sha256, val| is_root(val));
+        let (added, removed, changed) = diff_certificates(&reference, &other, span);
+        let mut diff = Record::new();
+        diff.push("added", Value::list(added, span));
+        diff.push("removed", Value::list(removed, span));
+        diff.push("changed", Value::list(changed, span));
+        Ok(Value::record(diff, span))
+    }
+}
+
+/// Certificates added, removed and renewed, renewals being matched by subject
+fn diff_certificates(
+    old: &Record,
+    new: &Record,
+    span: Span,
+) -> (Vec<Value>, Vec<Value>, Vec<Value>) {
+    let mut removed = old
+        .iter()
+        .filter(|(sha256, _val)| !new.contains(sha256))
+        .map(|(_sha256, val)| val.clone())
+        .collect::<Vec<Value>>();
+    let mut added = new
+        .iter()
+        .filter(|(sha256, _val)| !old.contains(sha256))
+        .map(|(_sha256, val)| val.clone())
+        .collect::<Vec<Value>>();
+    let mut renewed = Vec::new();
+    removed.retain(|old| {
+        let Some(index) = added
+            .iter()
+            .position(|new| get_identity(new).is_some() && get_identity(new) == get_identity(old))
+        else {
+            return true;
+        };
+        let new = added.remove(index);
+        let column =
+            |val: &Value, column: &str| val.get_data_by_key(column).unwrap_or(Value::nothing(span));
+        let mut record = Record::new();
+        record.push("subject", get_identity(old).unwrap_or(Value::nothing(span)));
+        record.push("old_sha256", column(old, "sha256"));
+        record.push("new_sha256", column(&new, "sha256"));
+        record.push("old_expiration", column(old, "expiration"));
+        record.push("new_expiration", column(&new, "expiration"));
+        renewed.push(Value::record(record, span));
+        false
+    });
+    (added, removed, renewed)
+}
+
+fn get_identity(val: &Value) -> Option<Value> {
+    val.get_data_by_key("subject")
+}
+
+fn get_snapshot(snapshot: Value, cwd: &str, span: Span) -> Result<Value, CerError> {
+    match snapshot {
+        Value::String { val, .. } => {
+            let data = std::fs::read(Path::new(cwd).join(val)).map_err(CerError::Read)?;
+            let json = serde_json::from_slice(&data).map_err(CerError::Json)?;
+            Ok(json_to_value(json, span))
+        }
+        snapshot => Ok(snapshot),
+    }
+}
+
+fn get_snapshot_certificates(snapshot: Value, cwd: &str, span: Span) -> Result<Record, CerError> {
+    get_snapshot(snapshot, cwd, span)?
+        .into_record()
+        .ok()
+        .and_then(|mut snapshot| snapshot.remove("certificates"))
+        .and_then(|certificates| certificates.into_record().ok())
+        .ok_or(CerError::MissingColumn("certificates"))
+}
+
+pub fn json_to_value(json: serde_json::Value, span: Span) -> Value {
+    match json {
+        serde_json::Value::Null => Value::nothing(span),
+        serde_json::Value::Bool(val) => Value::bool(val, span),
+        serde_json::Value::Number(val) => match val.as_i64() {
+            Some(val) => Value::int(val, span),
+            None => Value::float(val.as_f64().unwrap_or_default(), span),
+        },
+        serde_json::Value::String(val) => Value::string(val, span),
+        serde_json::Value::Array(vals) => Value::list(
+            vals.into_iter()
+                .map(|val| json_to_value(val, span))
+                .collect(),
+            span,
+        ),
+        serde_json::Value::Object(vals) => Value::record(
+            vals.into_iter()
+                .map(|(column, val)| (column, json_to_value(val, span)))
+                .collect(),
+            span,
+        ),
+    }
+}
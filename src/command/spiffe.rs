@@ -0,0 +1,193 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Value};
+use x509_parser::{
+    certificate::X509Certificate, extensions::GeneralName, oid_registry::OID_X509_EXT_KEY_USAGE,
+    prelude::FromDer,
+};
+
+use crate::{
+    catalog::Catalog, certificate::get_certificate_ders, core::spiffe::parse_spiffe_id,
+    error::CerError, plugin::CerPlugin,
+};
+
+pub struct CerSpiffeVerify;
+
+impl SimplePluginCommand for CerSpiffeVerify {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer spiffe verify"
+    }
+
+    fn usage(&self) -> &str {
+        "Checks a leaf certificate against the SPIFFE X509-SVID rules"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The rules of the X509-SVID specification that a standard X.509 verification does not cover: a single valid SPIFFE ID as URI SAN, no CA flag, a critical key usage with digitalSignature and without keyCertSign or cRLSign. Broken MUST rules are issues, broken SHOULD rules are warnings. The signature chain is not verified."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open svid.pem | cer spiffe verify --trust-domain example.org",
+            description: "checks the SVID of a workload",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .named(
+                "trust-domain",
+                SyntaxShape::String,
+                "trust domain the SPIFFE ID must belong to",
+                Some('t'),
+            )
+            .category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "spiffe", "svid", "mesh", "workload"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let der = match input {
+            Value::String { val, .. } => get_certificate_ders(val)?
+                .into_iter()
+                .next()
+                .ok_or(CerError::NoCertificate("input"))?,
+            Value::Binary { val, .. } => val.clone(),
+            _ => {
+                return Err(
+                    LabeledError::new("Expected certificate input from pipeline").with_label(
+                        format!("requires certificate input; got {}", input.get_type()),
+                        call.head,
+                    ),
+                )
+            }
+        };
+        let (_rem, cer) = X509Certificate::from_der(&der).map_err(CerError::Der)?;
+        let trust_domain = call.get_flag::<String>("trust-domain")?;
+        let config = engine.get_plugin_config()?;
+        let catalog = Catalog::from_config(config.as_ref());
+        let record = get_svid_record(&cer, trust_domain.as_deref(), &catalog, span)?;
+        Ok(Value::record(record, span))
+    }
+}
+
+/// English template of a broken rule and its arguments, like the findings of cer lint
+type Finding = (&'static str, Vec<String>);
+
+fn get_svid_record(
+    cer: &X509Certificate,
+    expected_domain: Option<&str>,
+    catalog: &Catalog,
+    span: Span,
+) -> Result<Record, CerError> {
+    let mut issues: Vec<Finding> = Vec::new();
+    let mut warnings: Vec<Finding> = Vec::new();
+
+    // X509-SVID section 2: exactly one URI SAN, the SPIFFE ID
+    let sans = cer.subject_alternative_name().map_err(CerError::San)?;
+    let uris = sans
+        .iter()
+        .flat_map(|sans| &sans.value.general_names)
+        .filter_map(|name| match name {
+            GeneralName::URI(uri) => Some(*uri),
+            _ => None,
+        })
+        .collect::<Vec<&str>>();
+    if uris.len() != 1 {
+        issues.push((
+            "an SVID must have exactly one URI SAN, it has {0}",
+            vec![uris.len().to_string()],
+        ));
+    }
+    let id = uris.first().copied();
+    let (trust_domain, path) = match id.map(parse_spiffe_id) {
+        Some(Ok((trust_domain, path))) => (Some(trust_domain), Some(path)),
+        Some(Err(rule)) => {
+            issues.push((rule, vec![]));
+            (None, None)
+        }
+        None => (None, None),
+    };
+    if let (Some(expected), Some(trust_domain)) = (expected_domain, trust_domain) {
+        if expected != trust_domain {
+            issues.push((
+                "trust domain {0} is not the expected {1}",
+                vec![trust_domain.into(), expected.into()],
+            ));
+        }
+    }
+    // the bare trust domain identifies the trust domain itself, not a workload
+    if path == Some("") {
+        warnings.push(("the SPIFFE ID of a leaf SVID should have a path", vec![]));
+    }
+
+    // X509-SVID section 4: leaf certificates
+    if cer.is_ca() {
+        issues.push((
+            "a leaf SVID must not have the basic constraints CA flag",
+            vec![],
+        ));
+    }
+    match cer.key_usage().map_err(CerError::Extension)? {
+        Some(key_usage) => {
+            if !key_usage.critical {
+                issues.push(("the key usage of an SVID must be critical", vec![]));
+            }
+            if !key_usage.value.digital_signature() {
+                issues.push((
+                    "a leaf SVID must have the digitalSignature key usage",
+                    vec![],
+                ));
+            }
+            if key_usage.value.key_cert_sign() || key_usage.value.crl_sign() {
+                issues.push((
+                    "a leaf SVID must not have the keyCertSign or cRLSign key usage",
+                    vec![],
+                ));
+            }
+        }
+        None => issues.push((
+            "an SVID must have the key usage extension {0}",
+            vec![OID_X509_EXT_KEY_USAGE.to_id_string()],
+        )),
+    }
+    match cer.extended_key_usage().map_err(CerError::Extension)? {
+        Some(eku) if !eku.value.server_auth || !eku.value.client_auth => warnings.push((
+            "a leaf SVID should have the serverAuth and clientAuth extended key usages",
+            vec![],
+        )),
+        _ => {}
+    }
+
+    let texts = |findings: Vec<Finding>| {
+        let values = findings
+            .iter()
+            .map(|(template, args)| {
+                let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+                Value::string(catalog.text(template, &args), span)
+            })
+            .collect();
+        Value::list(values, span)
+    };
+    let string =
+        |string: Option<&str>| string.map_or(Value::nothing(span), |s| Value::string(s, span));
+    let mut record = Record::new();
+    record.push("spiffe_id", string(id));
+    record.push("trust_domain", string(trust_domain));
+    record.push("path", string(path));
+    record.push("valid", Value::bool(issues.is_empty(), span));
+    record.push("issues", texts(issues));
+    record.push("warnings", texts(warnings));
+    Ok(record)
+}
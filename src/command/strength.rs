@@ -0,0 +1,136 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Record, Signature, Value};
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::{
+    certificate::{get_certificate_ders, get_expiration},
+    core::key::{
+        get_hash_bits, get_key_algorithm, get_key_size, get_security_bits, get_signature_hash,
+    },
+    error::CerError,
+    plugin::CerPlugin,
+};
+
+pub struct CerStrength;
+
+impl SimplePluginCommand for CerStrength {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        "cer strength"
+    }
+
+    fn usage(&self) -> &str {
+        "Summarizes the weakest key, signature hash and expiration of a certificate chain"
+    }
+
+    fn extra_usage(&self) -> &str {
+        "The strength of keys and hashes is given in security bits, following NIST SP 800-57. Self-signed certificates are left out of the hash summary, as their signature is not checked by clients. The chain is summarized as given, it is not verified."
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "open fullchain.pem | cer strength",
+            description: "shows the weakest link of a chain",
+            result: None,
+        }]
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).category(Category::System)
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["certificate", "chain", "strength", "security", "weakest"]
+    }
+
+    fn run(
+        &self,
+        _plugin: &CerPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = input.span();
+        let Value::String { val, .. } = input else {
+            return Err(
+                LabeledError::new("Expected certificate input from pipeline").with_label(
+                    format!("requires certificate chain input; got {}", input.get_type()),
+                    call.head,
+                ),
+            );
+        };
+        let ders = get_certificate_ders(val)?;
+        let mut key: Option<(usize, String, usize, String)> = None;
+        let mut hash: Option<(usize, String, String)> = None;
+        let mut expiration: Option<(Value, String)> = None;
+        for der in &ders {
+            let (_rem, cer) = X509Certificate::from_der(der).map_err(CerError::Der)?;
+            let subject = cer.subject().to_string();
+
+            let algorithm = get_key_algorithm(cer.public_key());
+            let size = get_key_size(cer.public_key());
+            let bits = get_security_bits(&algorithm, size);
+            if key.as_ref().is_none_or(|(weakest, ..)| bits < *weakest) {
+                key = Some((bits, algorithm, size, subject.clone()));
+            }
+
+            if cer.subject() != cer.issuer() {
+                let name = get_signature_hash(&cer.signature_algorithm);
+                let bits = get_hash_bits(&name);
+                if hash.as_ref().is_none_or(|(weakest, ..)| bits < *weakest) {
+                    hash = Some((bits, name, subject.clone()));
+                }
+            }
+
+            let not_after = get_expiration(&cer, span)?;
+            let earlier = match (&expiration, &not_after) {
+                (Some((Value::Date { val: earliest, .. }, _)), Value::Date { val, .. }) => {
+                    val < earliest
+                }
+                _ => true,
+            };
+            if earlier {
+                expiration = Some((not_after, subject));
+            }
+        }
+        let (
+            Some((key_bits, algorithm, size, key_subject)),
+            Some((expiration, expiration_subject)),
+        ) = (key, expiration)
+        else {
+            return Err(CerError::NoCertificate("input").into());
+        };
+
+        let security_bits = hash
+            .as_ref()
+            .map_or(key_bits, |(hash_bits, ..)| key_bits.min(*hash_bits));
+
+        let mut record = Record::new();
+        record.push("certificates", Value::int(ders.len() as i64, span));
+        record.push("key_bits", Value::int(key_bits as i64, span));
+        record.push("weakest_key_algorithm", Value::string(algorithm, span));
+        record.push("weakest_key_size", Value::int(size as i64, span));
+        record.push("weakest_key_subject", Value::string(key_subject, span));
+        match hash {
+            Some((hash_bits, name, subject)) => {
+                record.push("hash_bits", Value::int(hash_bits as i64, span));
+                record.push("weakest_hash", Value::string(name, span));
+                record.push("weakest_hash_subject", Value::string(subject, span));
+            }
+            // a chain made only of a self-signed certificate
+            None => {
+                record.push("hash_bits", Value::nothing(span));
+                record.push("weakest_hash", Value::nothing(span));
+                record.push("weakest_hash_subject", Value::nothing(span));
+            }
+        }
+        record.push("security_bits", Value::int(security_bits as i64, span));
+        record.push("earliest_expiration", expiration);
+        record.push(
+            "earliest_expiration_subject",
+            Value::string(expiration_subject, span),
+        );
+        Ok(Value::record(record, span))
+    }
+}
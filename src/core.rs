@@ -0,0 +1,15 @@
+//! Parsing and analysis that does no I/O and calls no platform APIs,
+//! so it can be reused by other plugin targets and tested in isolation.
+//! It uses std but not nushell, wasm/ checks that it builds for wasm32 on its own.
+
+pub mod armor;
+pub mod config;
+pub mod detect;
+pub mod dn;
+pub mod error;
+pub mod filter;
+pub mod host;
+pub mod key;
+pub mod limits;
+pub mod oid;
+pub mod spiffe;
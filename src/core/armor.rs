@@ -0,0 +1,86 @@
+use crate::core::error::CoreError;
+
+const LINE_LENGTH: usize = 64;
+
+fn is_base64(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '+' || c == '/'
+}
+
+fn malformed(line: usize, reason: &'static str) -> CoreError {
+    CoreError::Armor { line, reason }
+}
+
+/// Validates PEM text against the strict grammar of RFC 7468 section 3.
+/// Text outside of the encapsulation boundaries is allowed, as in the RFC.
+pub fn check_strict(text: &str) -> Result<(), CoreError> {
+    if !text.ends_with('\n') {
+        return Err(malformed(text.lines().count(), "missing final newline"));
+    }
+    let mut label: Option<&str> = None;
+    let mut lines: Vec<&str> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match label {
+            None => {
+                if let Some(rest) = line.strip_prefix("-----BEGIN ") {
+                    let begin = rest
+                        .strip_suffix("-----")
+                        .ok_or(malformed(number, "malformed BEGIN boundary"))?;
+                    if begin.is_empty()
+                        || begin.starts_with([' ', '-'])
+                        || begin.ends_with([' ', '-'])
+                        || begin.contains("  ")
+                        || !begin.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+                    {
+                        return Err(malformed(number, "invalid label"));
+                    }
+                    label = Some(begin);
+                    lines.clear();
+                }
+            }
+            Some(begin) => {
+                if let Some(rest) = line.strip_prefix("-----END ") {
+                    if rest.strip_suffix("-----") != Some(begin) {
+                        return Err(malformed(number, "END label does not match BEGIN label"));
+                    }
+                    check_base64_lines(&lines, number)?;
+                    label = None;
+                } else {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    match label {
+        Some(_) => Err(malformed(text.lines().count(), "missing END boundary")),
+        None => Ok(()),
+    }
+}
+
+fn check_base64_lines(lines: &[&str], end: usize) -> Result<(), CoreError> {
+    let start = end - lines.len();
+    let Some((last, full)) = lines.split_last() else {
+        return Err(malformed(end, "empty encapsulated text"));
+    };
+    for (index, line) in full.iter().enumerate() {
+        if line.len() != LINE_LENGTH {
+            return Err(malformed(start + index, "base64 line is not 64 characters"));
+        }
+        if !line.chars().all(is_base64) {
+            return Err(malformed(start + index, "invalid base64 character"));
+        }
+    }
+    let data = last.trim_end_matches('=');
+    if last.is_empty() || last.len() > LINE_LENGTH {
+        return Err(malformed(end - 1, "invalid last base64 line length"));
+    }
+    if last.len() - data.len() > 2 || !data.chars().all(is_base64) {
+        return Err(malformed(end - 1, "invalid base64 character"));
+    }
+    // full lines are always a multiple of 4 characters
+    if !last.len().is_multiple_of(4) {
+        return Err(malformed(end - 1, "base64 text is not padded"));
+    }
+    Ok(())
+}
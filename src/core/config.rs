@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+/// A certificate referenced from a web server configuration
+pub struct Binding {
+    pub format: &'static str,
+    pub config: PathBuf,
+    pub line: usize,
+    pub server: Option<String>,
+    pub cert: PathBuf,
+    pub key: Option<PathBuf>,
+}
+
+pub fn scan_config(config: &Path, text: &str) -> Vec<Binding> {
+    let mut bindings = scan_nginx(config, text);
+    bindings.extend(scan_apache(config, text));
+    bindings.extend(scan_haproxy(config, text));
+    bindings.sort_by_key(|binding| binding.line);
+    bindings
+}
+
+fn resolve(config: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path.trim_matches(|c| c == '"' || c == '\''));
+    match config.parent() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+struct NginxServer {
+    depth: usize,
+    names: Vec<String>,
+    certs: Vec<(usize, String)>,
+    keys: Vec<String>,
+}
+
+// nginx: `server { server_name ...; ssl_certificate ...; ssl_certificate_key ...; }`
+fn scan_nginx(config: &Path, text: &str) -> Vec<Binding> {
+    let mut bindings = Vec::new();
+    let mut servers: Vec<NginxServer> = Vec::new();
+    let mut depth = 0;
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut start = 0;
+    for (index, line) in text.lines().enumerate() {
+        for c in strip_comment(line).chars() {
+            if !c.is_whitespace() && c != ';' && c != '{' && c != '}' {
+                if words.is_empty() && word.is_empty() {
+                    start = index + 1;
+                }
+                word.push(c);
+                continue;
+            }
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            match c {
+                '{' => {
+                    depth += 1;
+                    if words.first().map(String::as_str) == Some("server") {
+                        servers.push(NginxServer {
+                            depth,
+                            names: Vec::new(),
+                            certs: Vec::new(),
+                            keys: Vec::new(),
+                        });
+                    }
+                    words.clear();
+                }
+                '}' => {
+                    if servers.last().map(|server| server.depth) == Some(depth) {
+                        if let Some(server) = servers.pop() {
+                            let name = server.names.first().cloned();
+                            for (i, (line, cert)) in server.certs.iter().enumerate() {
+                                bindings.push(Binding {
+                                    format: "nginx",
+                                    config: config.to_path_buf(),
+                                    line: *line,
+                                    server: name.clone(),
+                                    cert: resolve(config, cert),
+                                    key: server.keys.get(i).map(|key| resolve(config, key)),
+                                });
+                            }
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                    words.clear();
+                }
+                ';' => {
+                    if let (Some(server), Some(directive)) = (servers.last_mut(), words.first()) {
+                        match directive.as_str() {
+                            "server_name" => server.names.extend(words.iter().skip(1).cloned()),
+                            "ssl_certificate" => {
+                                if let Some(path) = words.get(1) {
+                                    server.certs.push((start, path.clone()));
+                                }
+                            }
+                            "ssl_certificate_key" => {
+                                if let Some(path) = words.get(1) {
+                                    server.keys.push(path.clone());
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    words.clear();
+                }
+                _ => {}
+            }
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    bindings
+}
+
+// apache: `<VirtualHost> ServerName ... SSLCertificateFile ... SSLCertificateKeyFile ... </VirtualHost>`
+fn scan_apache(config: &Path, text: &str) -> Vec<Binding> {
+    let mut bindings = Vec::new();
+    let mut server: Option<String> = None;
+    let mut cert: Option<(usize, String)> = None;
+    let mut key: Option<String> = None;
+    let mut flush =
+        |server: &Option<String>, cert: &mut Option<(usize, String)>, key: &mut Option<String>| {
+            if let Some((line, path)) = cert.take() {
+                bindings.push(Binding {
+                    format: "apache",
+                    config: config.to_path_buf(),
+                    line,
+                    server: server.clone(),
+                    cert: resolve(config, &path),
+                    key: key.take().map(|key| resolve(config, &key)),
+                });
+            }
+            key.take();
+        };
+    for (index, line) in text.lines().enumerate() {
+        let mut words = strip_comment(line).split_whitespace();
+        let Some(directive) = words.next() else {
+            continue;
+        };
+        let directive = directive.to_lowercase();
+        let value = words.next().map(str::to_string);
+        match directive.as_str() {
+            "servername" => server = value,
+            "sslcertificatefile" => cert = value.map(|path| (index + 1, path)),
+            "sslcertificatekeyfile" => key = value,
+            "<virtualhost" | "</virtualhost>" => {
+                flush(&server, &mut cert, &mut key);
+                server = None;
+            }
+            _ => {}
+        }
+    }
+    flush(&server, &mut cert, &mut key);
+    bindings
+}
+
+// haproxy: `frontend name` sections with `bind ... ssl crt <path> [crt <path>]`
+fn scan_haproxy(config: &Path, text: &str) -> Vec<Binding> {
+    let mut bindings = Vec::new();
+    let mut section: Option<String> = None;
+    for (index, line) in text.lines().enumerate() {
+        let words = strip_comment(line)
+            .split_whitespace()
+            .collect::<Vec<&str>>();
+        match words.first() {
+            Some(&("frontend" | "listen" | "backend" | "defaults" | "global")) => {
+                section = words.get(1).map(|name| name.to_string());
+            }
+            Some(&"bind") => {
+                let crts = words
+                    .windows(2)
+                    .filter(|pair| pair[0] == "crt")
+                    .map(|pair| pair[1]);
+                // directories and the .key files next to the certificates are resolved by the caller
+                bindings.extend(crts.map(|crt| Binding {
+                    format: "haproxy",
+                    config: config.to_path_buf(),
+                    line: index + 1,
+                    server: section.clone(),
+                    cert: resolve(config, crt),
+                    key: None,
+                }));
+            }
+            _ => {}
+        }
+    }
+    bindings
+}
@@ -0,0 +1,184 @@
+use data_encoding::BASE64;
+use x509_parser::{
+    certificate::X509Certificate, certification_request::X509CertificationRequest,
+    der_parser::asn1_rs::Tag, prelude::FromDer,
+};
+
+use crate::core::key::{parse_sequence, sequence_items};
+
+/// What an input appears to be, and the evidence for it
+pub struct Detection {
+    pub format: String,
+    pub reasons: Vec<String>,
+    pub labels: Vec<String>,
+    pub entropy: f64,
+}
+
+/// Shannon entropy in bits per byte, close to 8 for encrypted or compressed data
+pub fn get_entropy(data: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in data {
+        counts[*byte as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn get_labels(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("-----BEGIN ")?
+                .strip_suffix("-----")
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+// PFX ::= SEQUENCE { version INTEGER (3), authSafe ContentInfo, macData OPTIONAL }
+fn is_pfx(data: &[u8]) -> bool {
+    let Some(items) = parse_sequence(data) else {
+        return false;
+    };
+    let version = items
+        .first()
+        .filter(|version| version.tag() == Tag::Integer)
+        .map(|version| version.data);
+    let content_type = items
+        .get(1)
+        .and_then(sequence_items)
+        .and_then(|content| Some(content.first()?.as_oid().ok()?.to_id_string()));
+    version == Some(&[3][..])
+        && content_type.is_some_and(|oid| oid.starts_with("1.2.840.113549.1.7."))
+}
+
+// PrivateKeyInfo ::= SEQUENCE { version INTEGER, algorithm AlgorithmIdentifier, privateKey OCTET STRING }
+fn is_pkcs8(data: &[u8]) -> bool {
+    parse_sequence(data).is_some_and(|items| {
+        items.len() >= 3
+            && items[0].tag() == Tag::Integer
+            && items[1].tag() == Tag::Sequence
+            && items[2].tag() == Tag::OctetString
+    })
+}
+
+fn detect_binary(data: &[u8], reasons: &mut Vec<String>) -> &'static str {
+    let magic = [
+        (
+            &[0xfe, 0xed, 0xfe, 0xed][..],
+            "jks",
+            "starts with the JKS magic FEEDFEED",
+        ),
+        (
+            &[0xce, 0xce, 0xce, 0xce][..],
+            "jceks",
+            "starts with the JCEKS magic CECECECE",
+        ),
+        (
+            &[0x50, 0x4b, 0x03, 0x04][..],
+            "zip",
+            "starts with the zip magic PK",
+        ),
+        (&[0x1f, 0x8b][..], "gzip", "starts with the gzip magic 1F8B"),
+    ];
+    if let Some((_magic, format, reason)) = magic.iter().find(|(magic, ..)| data.starts_with(magic))
+    {
+        reasons.push(reason.to_string());
+        return format;
+    }
+    if data.first() != Some(&0x30) {
+        reasons.push("does not start with a DER SEQUENCE (30)".into());
+        return "unknown";
+    }
+    reasons.push("starts with a DER SEQUENCE (30)".into());
+    if is_pfx(data) {
+        reasons.push("version 3 followed by a PKCS#7 content info, the PFX layout".into());
+        "pfx"
+    } else if X509Certificate::from_der(data).is_ok() {
+        reasons.push("parses as a DER certificate".into());
+        "der-certificate"
+    } else if X509CertificationRequest::from_der(data).is_ok() {
+        reasons.push("parses as a DER certificate request".into());
+        "der-request"
+    } else if is_pkcs8(data) {
+        reasons.push("version, algorithm and octet string, the PKCS#8 layout".into());
+        "der-private-key"
+    } else {
+        reasons.push("the SEQUENCE matches no known layout".into());
+        "unknown"
+    }
+}
+
+// whole lines of base64, padded to a multiple of 4, so words and short tokens are not taken for it
+fn is_base64(text: &str) -> bool {
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>();
+    let len = lines.iter().map(|line| line.len()).sum::<usize>();
+    len >= 16
+        && len.is_multiple_of(4)
+        && lines.iter().all(|line| {
+            line.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=')
+        })
+}
+
+/// Detects the format of an input without parsing its certificates fully
+pub fn detect(data: &[u8], text: bool) -> Detection {
+    let mut reasons = Vec::new();
+    let mut labels = Vec::new();
+    let string = std::str::from_utf8(data).ok();
+    let format = match string {
+        Some(string) if string.contains("-----BEGIN ") => {
+            labels = get_labels(string);
+            reasons.push(format!("found {} PEM armor lines", labels.len()));
+            "pem".to_string()
+        }
+        Some(string) if is_base64(string) => {
+            let compact = string.split_whitespace().collect::<String>();
+            match BASE64.decode(compact.as_bytes()) {
+                Ok(decoded) => {
+                    reasons.push("text is base64 without PEM armor".into());
+                    let inner = detect_binary(&decoded, &mut reasons);
+                    format!("base64-{}", inner)
+                }
+                Err(err) => {
+                    reasons.push(format!(
+                        "text looks like base64 but does not decode: {}",
+                        err
+                    ));
+                    "unknown".to_string()
+                }
+            }
+        }
+        Some(_) if text => {
+            reasons.push("text without PEM armor".into());
+            "unknown".to_string()
+        }
+        _ => detect_binary(data, &mut reasons).to_string(),
+    };
+    let entropy = get_entropy(data);
+    if format == "unknown" && entropy > 7.5 {
+        reasons.push("high entropy, the data may be encrypted or compressed".into());
+    }
+    if text && format == "base64-pfx" {
+        reasons.push(
+            "cer reads a pfx from binary input, pipe the text through `decode base64` first".into(),
+        );
+    }
+    Detection {
+        format,
+        reasons,
+        labels,
+        entropy,
+    }
+}
@@ -0,0 +1,200 @@
+use x509_parser::der_parser::asn1_rs::Tag;
+
+use crate::core::error::CoreError;
+
+/// One attribute of a distinguished name, like `CN=example.com`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DnAttribute {
+    pub name: String,
+    pub value: String,
+}
+
+/// Distinguished name as a list of RDNs, in the RFC 4514 order (most specific first)
+pub type Dn = Vec<Vec<DnAttribute>>;
+
+fn malformed(dn: &str, reason: &'static str) -> CoreError {
+    CoreError::Dn {
+        dn: dn.to_string(),
+        reason,
+    }
+}
+
+/// Parses a RFC 4514 string, also accepting the RFC 1779 quoting and `;` separators
+pub fn parse_dn(dn: &str) -> Result<Dn, CoreError> {
+    let mut rdns = Vec::new();
+    let mut rdn = Vec::new();
+    let mut name = String::new();
+    let mut value = String::new();
+    // hex escapes are bytes of an UTF-8 sequence, decoded once the sequence ends
+    let mut bytes = Vec::new();
+    let mut in_value = false;
+    let mut quoted = false;
+    let mut chars = dn.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let Some(next) = chars.next() else {
+                return Err(malformed(dn, "trailing escape character"));
+            };
+            if let (Some(high), Some(low)) =
+                (next.to_digit(16), chars.peek().and_then(|c| c.to_digit(16)))
+            {
+                chars.next();
+                bytes.push((high * 16 + low) as u8);
+                continue;
+            }
+            flush_bytes(dn, &mut bytes, &mut value)?;
+            value.push(next);
+            continue;
+        }
+        flush_bytes(dn, &mut bytes, &mut value)?;
+        match c {
+            '"' if in_value => quoted = !quoted,
+            '=' if !in_value => in_value = true,
+            ',' | ';' | '+' if !quoted => {
+                rdn.push(get_attribute(dn, &name, &value, in_value)?);
+                name.clear();
+                value.clear();
+                in_value = false;
+                if c != '+' {
+                    rdns.push(std::mem::take(&mut rdn));
+                }
+            }
+            c if in_value => value.push(c),
+            c => name.push(c),
+        }
+    }
+    flush_bytes(dn, &mut bytes, &mut value)?;
+    if quoted {
+        return Err(malformed(dn, "unterminated quoted value"));
+    }
+    if !name.trim().is_empty() || in_value {
+        rdn.push(get_attribute(dn, &name, &value, in_value)?);
+    }
+    if !rdn.is_empty() {
+        rdns.push(rdn);
+    }
+    Ok(rdns)
+}
+
+fn flush_bytes(dn: &str, bytes: &mut Vec<u8>, value: &mut String) -> Result<(), CoreError> {
+    if !bytes.is_empty() {
+        let text = String::from_utf8(std::mem::take(bytes))
+            .map_err(|_err| malformed(dn, "hex escapes are not UTF-8"))?;
+        value.push_str(&text);
+    }
+    Ok(())
+}
+
+fn get_attribute(
+    dn: &str,
+    name: &str,
+    value: &str,
+    in_value: bool,
+) -> Result<DnAttribute, CoreError> {
+    let name = name.trim();
+    if !in_value || name.is_empty() {
+        return Err(malformed(dn, "attribute without a type or value"));
+    }
+    Ok(DnAttribute {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+/// Canonical attribute type names, so `emailAddress`, `E` and the OID all compare equal
+pub fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    let canonical = match name.to_ascii_lowercase().as_str() {
+        "cn" | "commonname" | "2.5.4.3" => "CN",
+        "sn" | "surname" | "2.5.4.4" => "SN",
+        "serialnumber" | "2.5.4.5" => "SERIALNUMBER",
+        "c" | "countryname" | "2.5.4.6" => "C",
+        "l" | "localityname" | "2.5.4.7" => "L",
+        "st" | "s" | "stateorprovincename" | "2.5.4.8" => "ST",
+        "street" | "streetaddress" | "2.5.4.9" => "STREET",
+        "o" | "organizationname" | "2.5.4.10" => "O",
+        "ou" | "organizationalunitname" | "2.5.4.11" => "OU",
+        "title" | "2.5.4.12" => "TITLE",
+        "givenname" | "gn" | "2.5.4.42" => "GIVENNAME",
+        "dc" | "domaincomponent" | "0.9.2342.19200300.100.1.25" => "DC",
+        "uid" | "userid" | "0.9.2342.19200300.100.1.1" => "UID",
+        "e" | "email" | "emailaddress" | "1.2.840.113549.1.9.1" => "EMAILADDRESS",
+        _ => return name.to_ascii_uppercase(),
+    };
+    canonical.to_string()
+}
+
+/// Case and whitespace folding of RFC 4518, with the caseIgnoreMatch rule for every attribute
+pub fn normalize_value(value: &str) -> String {
+    if value.starts_with('#') {
+        // BER encoded values are only compared byte for byte
+        return value.to_lowercase();
+    }
+    value
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Normalized form; attributes of a multi-valued RDN are sorted since their order is not significant
+pub fn normalize_dn(dn: &Dn) -> Dn {
+    dn.iter()
+        .map(|rdn| {
+            let mut rdn = rdn
+                .iter()
+                .map(|attribute| DnAttribute {
+                    name: normalize_name(&attribute.name),
+                    value: normalize_value(&attribute.value),
+                })
+                .collect::<Vec<DnAttribute>>();
+            rdn.sort();
+            rdn
+        })
+        .collect()
+}
+
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::new();
+    for (index, c) in value.chars().enumerate() {
+        let special = matches!(c, ',' | '+' | '"' | '\\' | '<' | '>' | ';' | '=')
+            || (index == 0 && (c == ' ' || c == '#'))
+            || (index == value.chars().count() - 1 && c == ' ');
+        if special {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn format_dn(dn: &Dn) -> String {
+    dn.iter()
+        .map(|rdn| {
+            rdn.iter()
+                .map(|attribute| format!("{}={}", attribute.name, escape_value(&attribute.value)))
+                .collect::<Vec<String>>()
+                .join("+")
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Text of a BMPString (UTF-16) or UniversalString (UTF-32), both big endian,
+/// or None for the other string types and malformed values
+pub fn decode_wide_string(tag: Tag, data: &[u8]) -> Option<String> {
+    match tag {
+        Tag::BmpString if data.len().is_multiple_of(2) => {
+            let units = data
+                .chunks_exact(2)
+                .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+                .collect::<Vec<u16>>();
+            String::from_utf16(&units).ok()
+        }
+        Tag::UniversalString if data.len().is_multiple_of(4) => data
+            .chunks_exact(4)
+            .map(|unit| char::from_u32(u32::from_be_bytes([unit[0], unit[1], unit[2], unit[3]])))
+            .collect(),
+        _ => None,
+    }
+}
@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+/// Failures of the parsing in core, the plugin turns them into a CerError
+#[derive(Error, Debug)]
+pub enum CoreError {
+    #[error("malformed PEM armor at line {line}: {reason}")]
+    Armor { line: usize, reason: &'static str },
+    #[error("cannot parse distinguished name {dn}: {reason}")]
+    Dn { dn: String, reason: &'static str },
+    #[error("input exceeds the limit of {limit} {what}")]
+    Limit { what: &'static str, limit: usize },
+}
@@ -0,0 +1,61 @@
+/// Glob patterns of hostnames and paths to keep or leave out of a batch operation
+#[derive(Default)]
+pub struct TargetFilter {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl TargetFilter {
+    /// A target is kept when one of its names is allowed, or there is no allowlist,
+    /// and none of its names is denied
+    pub fn allows(&self, names: &[&str]) -> bool {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| names.iter().any(|name| glob_matches(pattern, name)))
+        };
+        (self.allow.is_empty() || matches(&self.allow)) && !matches(&self.deny)
+    }
+}
+
+fn normalize(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| {
+            if c == '\\' {
+                '/'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+/// Matches `*` to any run of characters and `?` to one, ignoring ASCII case and path separators
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = normalize(pattern);
+    let text = normalize(text);
+    let (mut p, mut t) = (0, 0);
+    // position of the last star and of the text it matches up to, to backtrack to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
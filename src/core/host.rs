@@ -0,0 +1,18 @@
+/// Whether a certificate name covers a hostname, following RFC 6125 section 6.4:
+/// names compare without case or a trailing dot, and a wildcard is a whole
+/// left-most label standing for exactly one label
+pub fn name_covers(name: &str, host: &str) -> bool {
+    let name = normalize_host(name);
+    let host = normalize_host(host);
+    match name.strip_prefix("*.") {
+        Some(parent) => host
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == parent),
+        None => name == host,
+    }
+}
+
+/// Form of a DNS name that compares by RFC 6125: lower case, without a trailing dot
+pub fn normalize_host(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
@@ -0,0 +1,328 @@
+use std::{fmt, ops::Deref};
+
+use x509_parser::{
+    certificate::X509Certificate,
+    der_parser::asn1_rs::{Any, FromDer, Oid, Tag},
+    oid_registry::{
+        OID_EC_P256, OID_KEY_TYPE_DSA, OID_KEY_TYPE_EC_PUBLIC_KEY, OID_NIST_EC_P384,
+        OID_NIST_EC_P521, OID_PKCS1_RSAENCRYPTION, OID_PKCS1_RSASSAPSS, OID_SIG_ED25519,
+        OID_SIG_ED448,
+    },
+    pem::Pem,
+    public_key::PublicKey,
+    signature_algorithm::SignatureAlgorithm,
+    x509::{AlgorithmIdentifier, SubjectPublicKeyInfo},
+};
+use zeroize::Zeroize;
+
+use crate::core::oid::get_oid_name;
+
+/// Public half of a private key, as far as it can be recovered without doing any crypto
+pub enum KeyPublic {
+    Rsa(Vec<u8>),
+    Ec(Vec<u8>),
+}
+
+/// A PEM block that may hold a private key, its decoded contents are wiped when dropped
+pub struct KeyPem(pub Pem);
+
+impl Deref for KeyPem {
+    type Target = Pem;
+
+    fn deref(&self) -> &Pem {
+        &self.0
+    }
+}
+
+impl fmt::Debug for KeyPem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyPem({}, [redacted])", self.0.label)
+    }
+}
+
+impl Drop for KeyPem {
+    fn drop(&mut self) {
+        self.0.contents.zeroize();
+    }
+}
+
+pub fn is_private_key(pem: &Pem) -> bool {
+    pem.label.ends_with("PRIVATE KEY")
+}
+
+pub fn find_private_key(data: &[u8]) -> Option<KeyPem> {
+    Pem::iter_from_buffer(data)
+        .filter_map(Result::ok)
+        .map(KeyPem)
+        .find(|pem| is_private_key(pem))
+}
+
+pub fn get_key_public(pem: &Pem) -> Option<KeyPublic> {
+    match pem.label.as_str() {
+        "RSA PRIVATE KEY" => parse_pkcs1(&pem.contents),
+        "EC PRIVATE KEY" => parse_sec1(&pem.contents),
+        "PRIVATE KEY" => parse_pkcs8(&pem.contents),
+        // encrypted keys cannot be inspected without the password
+        _ => None,
+    }
+}
+
+/// Compares a private key with the public key of a certificate.
+/// Returns None when the key does not carry enough information to decide.
+pub fn key_matches(pem: &Pem, cer: &X509Certificate) -> Option<bool> {
+    let key = get_key_public(pem)?;
+    let matches = match (key, cer.public_key().parsed().ok()?) {
+        (KeyPublic::Rsa(modulus), PublicKey::RSA(rsa)) => {
+            trim_integer(&modulus) == trim_integer(rsa.modulus)
+        }
+        (KeyPublic::Ec(point), PublicKey::EC(ec)) => point == ec.data(),
+        _ => false,
+    };
+    Some(matches)
+}
+
+pub fn get_key_algorithm(spki: &SubjectPublicKeyInfo) -> String {
+    let oid = &spki.algorithm.algorithm;
+    let name = if *oid == OID_PKCS1_RSAENCRYPTION {
+        "rsa"
+    } else if *oid == OID_PKCS1_RSASSAPSS {
+        "rsa-pss"
+    } else if *oid == OID_KEY_TYPE_DSA {
+        "dsa"
+    } else if *oid == OID_SIG_ED25519 {
+        "ed25519"
+    } else if *oid == OID_SIG_ED448 {
+        "ed448"
+    } else if *oid == OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.as_oid().ok());
+        get_curve_algorithm(curve.as_ref())
+    } else {
+        return oid.to_id_string();
+    };
+    name.to_string()
+}
+
+fn get_curve_algorithm(curve: Option<&Oid>) -> &'static str {
+    match curve {
+        Some(curve) if *curve == OID_EC_P256 => "ec-p256",
+        Some(curve) if *curve == OID_NIST_EC_P384 => "ec-p384",
+        Some(curve) if *curve == OID_NIST_EC_P521 => "ec-p521",
+        _ => "ec",
+    }
+}
+
+fn get_curve_size(algorithm: &str) -> usize {
+    match algorithm {
+        "ec-p256" => 256,
+        "ec-p384" => 384,
+        "ec-p521" => 521,
+        _ => 0,
+    }
+}
+
+/// Algorithm and size in bits of a private key, like get_key_algorithm and get_key_size
+pub fn get_private_key_algorithm(pem: &Pem) -> Option<(String, usize)> {
+    match pem.label.as_str() {
+        "RSA PRIVATE KEY" => get_pkcs1_algorithm(&pem.contents),
+        "EC PRIVATE KEY" => get_sec1_algorithm(&pem.contents, None),
+        "PRIVATE KEY" => get_pkcs8_algorithm(&pem.contents),
+        _ => None,
+    }
+}
+
+fn get_pkcs1_algorithm(data: &[u8]) -> Option<(String, usize)> {
+    match parse_pkcs1(data)? {
+        KeyPublic::Rsa(modulus) => Some(("rsa".into(), bit_length(&modulus))),
+        KeyPublic::Ec(_) => None,
+    }
+}
+
+// the curve is in the parameters of the key, or of the PKCS#8 algorithm identifier
+fn get_sec1_algorithm(data: &[u8], curve: Option<Oid>) -> Option<(String, usize)> {
+    let items = parse_sequence(data)?;
+    let curve = curve.or_else(|| {
+        let parameters = items.iter().skip(2).find(|item| item.tag() == Tag(0))?;
+        let (_rem, curve) = Oid::from_der(parameters.data).ok()?;
+        Some(curve)
+    });
+    let algorithm = get_curve_algorithm(curve.as_ref());
+    Some((algorithm.into(), get_curve_size(algorithm)))
+}
+
+fn get_pkcs8_algorithm(data: &[u8]) -> Option<(String, usize)> {
+    let items = parse_sequence(data)?;
+    let algorithm = sequence_items(items.get(1)?)?;
+    let oid = algorithm.first()?.as_oid().ok()?;
+    let private_key = items.get(2)?;
+    if oid == OID_PKCS1_RSAENCRYPTION {
+        get_pkcs1_algorithm(private_key.data)
+    } else if oid == OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = algorithm.get(1).and_then(|curve| curve.as_oid().ok());
+        get_sec1_algorithm(private_key.data, curve)
+    } else if oid == OID_SIG_ED25519 {
+        Some(("ed25519".into(), 256))
+    } else if oid == OID_SIG_ED448 {
+        Some(("ed448".into(), 448))
+    } else {
+        None
+    }
+}
+
+/// Key size in bits, or 0 when it cannot be determined
+pub fn get_key_size(spki: &SubjectPublicKeyInfo) -> usize {
+    let oid = &spki.algorithm.algorithm;
+    if *oid == OID_SIG_ED25519 {
+        return 256;
+    }
+    if *oid == OID_SIG_ED448 {
+        return 448;
+    }
+    match spki.parsed() {
+        Ok(PublicKey::RSA(rsa)) => bit_length(rsa.modulus),
+        Ok(PublicKey::DSA(y)) => bit_length(y),
+        Ok(key) => key.key_size(),
+        Err(_) => 0,
+    }
+}
+
+/// Security strength in bits following NIST SP 800-57 part 1 table 2, or 0 when unknown
+pub fn get_security_bits(algorithm: &str, size: usize) -> usize {
+    match algorithm {
+        "rsa" | "rsa-pss" | "dsa" => match size {
+            15360.. => 256,
+            7680.. => 192,
+            3072.. => 128,
+            2048.. => 112,
+            1024.. => 80,
+            _ => 0,
+        },
+        "ed25519" => 128,
+        "ed448" => 224,
+        algorithm if algorithm.starts_with("ec") => size / 2,
+        _ => 0,
+    }
+}
+
+/// Status of a security strength per NIST SP 800-131A and the quantum transition of NIST IR 8547:
+/// 112 bits are deprecated after 2030, and every RSA and elliptic curve key is disallowed after 2035.
+/// Returns the status in the given year and the last year the strength is acceptable.
+pub fn get_strength_status(bits: usize, year: i32) -> (&'static str, Option<i32>) {
+    let until = match bits {
+        0 => return ("unknown", None),
+        1..=111 => return ("disallowed", None),
+        112 => 2030,
+        _ => 2035,
+    };
+    let status = match year {
+        year if year <= until => "acceptable",
+        ..=2035 => "deprecated",
+        _ => "disallowed",
+    };
+    (status, Some(until))
+}
+
+/// Collision resistance of a signature hash in bits, or 0 when it is broken or unknown
+pub fn get_hash_bits(hash: &str) -> usize {
+    match hash {
+        "sha1" => 63, // the SHAttered collision attack
+        "sha224" => 112,
+        "sha256" | "ed25519" => 128,
+        "sha384" => 192,
+        "sha512" => 256,
+        "ed448" => 224,
+        _ => 0,
+    }
+}
+
+/// Hash of a signature algorithm, like sha256, or the algorithm name when it has none
+pub fn get_signature_hash(algorithm: &AlgorithmIdentifier) -> String {
+    let name = match SignatureAlgorithm::try_from(algorithm) {
+        Ok(SignatureAlgorithm::RSASSA_PSS(params)) => get_oid_name(params.hash_algorithm_oid()),
+        _ => get_oid_name(&algorithm.algorithm),
+    };
+    let lowercase = name.to_lowercase();
+    ["md5", "sha1", "sha224", "sha256", "sha384", "sha512"]
+        .into_iter()
+        .find(|hash| lowercase.contains(hash))
+        .map_or(name, str::to_string)
+}
+
+fn bit_length(integer: &[u8]) -> usize {
+    let integer = trim_integer(integer);
+    match integer.first() {
+        Some(first) => (integer.len() - 1) * 8 + (8 - first.leading_zeros() as usize),
+        None => 0,
+    }
+}
+
+pub fn parse_sequence(data: &[u8]) -> Option<Vec<Any<'_>>> {
+    let (_rem, any) = Any::from_der(data).ok()?;
+    sequence_items(&any)
+}
+
+pub fn sequence_items<'a>(any: &Any<'a>) -> Option<Vec<Any<'a>>> {
+    if any.tag() != Tag::Sequence {
+        return None;
+    }
+    let mut items = Vec::new();
+    let mut rem = any.data;
+    while !rem.is_empty() {
+        let (next, item) = Any::from_der(rem).ok()?;
+        items.push(item);
+        rem = next;
+    }
+    Some(items)
+}
+
+// RSAPrivateKey ::= SEQUENCE { version, modulus, publicExponent, ... }
+fn parse_pkcs1(data: &[u8]) -> Option<KeyPublic> {
+    let items = parse_sequence(data)?;
+    let modulus = items.get(1)?;
+    if modulus.tag() != Tag::Integer {
+        return None;
+    }
+    Some(KeyPublic::Rsa(modulus.data.to_vec()))
+}
+
+// ECPrivateKey ::= SEQUENCE { version, privateKey, [0] parameters OPTIONAL, [1] publicKey OPTIONAL }
+fn parse_sec1(data: &[u8]) -> Option<KeyPublic> {
+    let items = parse_sequence(data)?;
+    let public = items.iter().skip(2).find(|item| item.tag() == Tag(1))?;
+    let (_rem, bits) = Any::from_der(public.data).ok()?;
+    if bits.tag() != Tag::BitString {
+        return None;
+    }
+    // the first byte of a bit string is the number of unused bits
+    let point = bits.data.get(1..)?;
+    Some(KeyPublic::Ec(point.to_vec()))
+}
+
+// PrivateKeyInfo ::= SEQUENCE { version, AlgorithmIdentifier, privateKey OCTET STRING, ... }
+fn parse_pkcs8(data: &[u8]) -> Option<KeyPublic> {
+    let items = parse_sequence(data)?;
+    let algorithm = sequence_items(items.get(1)?)?;
+    let oid = algorithm.first()?.as_oid().ok()?;
+    let private_key = items.get(2)?;
+    if private_key.tag() != Tag::OctetString {
+        return None;
+    }
+    if oid == OID_PKCS1_RSAENCRYPTION {
+        parse_pkcs1(private_key.data)
+    } else if oid == OID_KEY_TYPE_EC_PUBLIC_KEY {
+        parse_sec1(private_key.data)
+    } else {
+        None
+    }
+}
+
+fn trim_integer(data: &[u8]) -> &[u8] {
+    let start = data
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(data.len());
+    &data[start..]
+}
@@ -0,0 +1,45 @@
+use x509_parser::certificate::X509Certificate;
+
+use crate::core::error::CoreError;
+
+/// Limits on hostile or corrupted input, generous enough for real world bundles and stores
+pub struct Limits {
+    pub max_certificates: usize,
+    pub max_sans: usize,
+    pub max_extension_size: usize,
+    pub max_output_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_certificates: 100_000,
+            max_sans: 10_000,
+            max_extension_size: 1024 * 1024,
+            max_output_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+pub fn check_limit(what: &'static str, count: usize, limit: usize) -> Result<(), CoreError> {
+    if count > limit {
+        return Err(CoreError::Limit { what, limit });
+    }
+    Ok(())
+}
+
+pub fn check_certificate(cer: &X509Certificate, limits: &Limits) -> Result<(), CoreError> {
+    for extension in cer.extensions() {
+        check_limit(
+            "bytes per extension",
+            extension.value.len(),
+            limits.max_extension_size,
+        )?;
+    }
+    let sans = cer
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map_or(0, |sans| sans.value.general_names.len());
+    check_limit("subject alternative names", sans, limits.max_sans)
+}
@@ -0,0 +1,11 @@
+use x509_parser::{
+    der_parser::Oid,
+    objects::{oid2sn, oid_registry},
+};
+
+/// Short name of an OID, or its dotted form when it is not registered
+pub fn get_oid_name(oid: &Oid) -> String {
+    oid2sn(oid, oid_registry())
+        .map(str::to_string)
+        .unwrap_or_else(|_err| oid.to_id_string())
+}
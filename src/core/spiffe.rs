@@ -0,0 +1,34 @@
+/// Trust domain and path of a SPIFFE ID, following the SPIFFE-ID specification section 2.
+/// The error is the English template of the rule that is broken.
+pub fn parse_spiffe_id(id: &str) -> Result<(&str, &str), &'static str> {
+    if id.len() > 2048 {
+        return Err("SPIFFE ID is longer than 2048 bytes");
+    }
+    let Some(rest) = id.strip_prefix("spiffe://") else {
+        return Err("SPIFFE ID does not start with spiffe://");
+    };
+    let (trust_domain, path) = rest
+        .find('/')
+        .map_or((rest, ""), |slash| rest.split_at(slash));
+    if trust_domain.is_empty() {
+        return Err("SPIFFE ID has no trust domain");
+    }
+    // ports, user info, upper case, query and fragment are all excluded by the character set
+    let domain_char = |c: char| matches!(c, 'a'..='z' | '0'..='9' | '.' | '-' | '_');
+    if !trust_domain.chars().all(domain_char) {
+        return Err("SPIFFE trust domain has characters other than lowercase letters, digits, dots, dashes and underscores");
+    }
+    let path_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_');
+    for segment in path.split('/').skip(1) {
+        if segment.is_empty() {
+            return Err("SPIFFE ID path has an empty segment or a trailing slash");
+        }
+        if segment == "." || segment == ".." {
+            return Err("SPIFFE ID path has a relative segment");
+        }
+        if !segment.chars().all(path_char) {
+            return Err("SPIFFE ID path has characters other than letters, digits, dots, dashes and underscores");
+        }
+    }
+    Ok((trust_domain, path))
+}
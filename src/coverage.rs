@@ -0,0 +1,92 @@
+use nu_protocol::{Record, Span, Value};
+
+use crate::{certificate::CerResult, core::host::name_covers, error::CerError};
+
+/// Which certificates cover each host, the hosts no certificate covers,
+/// and the certificates covering none of the hosts
+pub fn get_coverage(vals: &[Value], hosts: Vec<String>, span: Span) -> CerResult<Value> {
+    let names = vals
+        .iter()
+        .map(get_names)
+        .collect::<CerResult<Vec<Vec<String>>>>()?;
+    let mut used = vec![false; vals.len()];
+    let mut covered_hosts = Vec::new();
+    let mut uncovered = Vec::new();
+    for host in hosts {
+        let mut certificates = Vec::new();
+        for (index, val) in vals.iter().enumerate() {
+            if names[index].iter().any(|name| name_covers(name, &host)) {
+                used[index] = true;
+                certificates.push(get_summary(val, span));
+            }
+        }
+        if certificates.is_empty() {
+            uncovered.push(Value::string(host.clone(), span));
+        }
+        let mut record = Record::new();
+        record.push("host", Value::string(host, span));
+        record.push("covered", Value::bool(!certificates.is_empty(), span));
+        record.push("certificates", Value::list(certificates, span));
+        covered_hosts.push(Value::record(record, span));
+    }
+    let unused = vals
+        .iter()
+        .zip(used)
+        .filter(|(_val, used)| !used)
+        .map(|(val, _used)| val.clone())
+        .collect();
+
+    let mut record = Record::new();
+    record.push("hosts", Value::list(covered_hosts, span));
+    record.push("uncovered", Value::list(uncovered, span));
+    record.push("unused", Value::list(unused, span));
+    Ok(Value::record(record, span))
+}
+
+fn get_strings(value: Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::List { vals, .. }) => vals
+            .into_iter()
+            .filter_map(|val| val.into_string().ok())
+            .collect(),
+        Some(Value::String { val, .. }) => vec![val],
+        _ => Vec::new(),
+    }
+}
+
+// clients only fall back to the common name when there are no SANs
+fn get_names(certificate: &Value) -> CerResult<Vec<String>> {
+    let sans = get_strings(certificate.get_data_by_key("san"));
+    // a list cut by cer without --full-sans would leave the hosts past it uncovered,
+    // san_count also counts the kinds --san-types moves to san_other
+    let truncated = certificate
+        .get_data_by_key("san_truncated")
+        .and_then(|truncated| truncated.as_bool().ok())
+        .unwrap_or_default();
+    if truncated {
+        let count = certificate
+            .get_data_by_key("san_count")
+            .and_then(|count| count.as_int().ok())
+            .unwrap_or_default();
+        return Err(CerError::TruncatedSans {
+            shown: sans.len(),
+            count,
+        });
+    }
+    if sans.is_empty() {
+        Ok(get_strings(certificate.get_data_by_key("cn")))
+    } else {
+        Ok(sans)
+    }
+}
+
+/// The columns that tell the certificates covering a host apart
+fn get_summary(certificate: &Value, span: Span) -> Value {
+    let mut record = Record::new();
+    for column in ["cn", "expiration", "sha256"] {
+        if let Some(value) = certificate.get_data_by_key(column) {
+            record.push(column, value);
+        }
+    }
+    Value::record(record, span)
+}
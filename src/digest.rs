@@ -0,0 +1,77 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use data_encoding::HEXLOWER;
+use nu_protocol::Value;
+use sha2::{Digest, Sha256};
+
+fn canonical_date(date: &DateTime<FixedOffset>) -> String {
+    date.with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn quote(val: &str) -> String {
+    serde_json::Value::String(val.to_string()).to_string()
+}
+
+/// JSON with sorted keys and no whitespace. Dates are written in UTC with second precision so
+/// the digest survives a `to json`/`open` round trip; strings are kept byte for byte.
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Record { val, .. } => {
+            let mut columns = val.iter().collect::<Vec<(&String, &Value)>>();
+            columns.sort_by_key(|(column, _val)| *column);
+            let columns = columns
+                .into_iter()
+                .map(|(column, val)| format!("{}:{}", quote(column), canonical_json(val)))
+                .collect::<Vec<String>>();
+            format!("{{{}}}", columns.join(","))
+        }
+        Value::List { vals, .. } => {
+            let vals = vals.iter().map(canonical_json).collect::<Vec<String>>();
+            format!("[{}]", vals.join(","))
+        }
+        Value::Date { val, .. } => quote(&canonical_date(val)),
+        Value::String { val, .. } => quote(val),
+        Value::Bool { val, .. } => val.to_string(),
+        Value::Int { val, .. } => val.to_string(),
+        Value::Float { val, .. } => {
+            serde_json::Number::from_f64(*val).map_or("null".into(), |number| number.to_string())
+        }
+        Value::Filesize { val, .. } => val.to_string(),
+        Value::Duration { val, .. } => val.to_string(),
+        Value::Binary { val, .. } => quote(&HEXLOWER.encode(val)),
+        _ => "null".into(),
+    }
+}
+
+/// Value with the conventions of canonical_json, so `to json` writes the same bytes on every
+/// machine: sorted columns, UTC dates and binary as hex strings, file sizes and durations as ints
+pub fn json_safe(value: Value) -> Value {
+    let span = value.span();
+    match value {
+        Value::Record { val, .. } => {
+            let mut columns = val
+                .into_owned()
+                .into_iter()
+                .collect::<Vec<(String, Value)>>();
+            columns.sort_by(|(column, _val), (other, _other)| column.cmp(other));
+            let record = columns
+                .into_iter()
+                .map(|(column, val)| (column, json_safe(val)))
+                .collect();
+            Value::record(record, span)
+        }
+        Value::List { vals, .. } => Value::list(vals.into_iter().map(json_safe).collect(), span),
+        Value::Date { val, .. } => Value::string(canonical_date(&val), span),
+        value @ Value::String { .. } => value,
+        Value::Float { val, .. } if !val.is_finite() => Value::nothing(span),
+        Value::Filesize { val, .. } => Value::int(val, span),
+        Value::Duration { val, .. } => Value::int(val, span),
+        Value::Binary { val, .. } => Value::string(HEXLOWER.encode(&val), span),
+        value @ (Value::Bool { .. } | Value::Int { .. } | Value::Float { .. }) => value,
+        _ => Value::nothing(span),
+    }
+}
+
+pub fn get_digest(value: &Value) -> String {
+    HEXLOWER.encode(&Sha256::digest(canonical_json(value).as_bytes()))
+}
@@ -6,14 +6,22 @@ use x509_parser::{
     nom::Err as NomErr,
 };
 
+use crate::core::error::CoreError;
+
 #[derive(Error, Debug)]
 pub enum CerError {
     #[error("cannot read certificate")]
     Pem(#[source] PEMError),
+    #[error("malformed PEM armor at line {line}: {reason}")]
+    Armor { line: usize, reason: &'static str },
     #[error("cannot parse certificate")]
     Parse(#[source] NomErr<X509Error>),
+    #[error("cannot parse certificate request")]
+    Request(#[source] NomErr<X509Error>),
     #[error("cannot read common name")]
     CommonName(#[source] Utf8Error),
+    #[error("cannot read email address")]
+    Email(#[source] Utf8Error),
     #[error("cannot read friendly name")]
     FriendlyName(#[source] std::io::Error),
     #[error("cannot read description")]
@@ -22,30 +30,121 @@ pub enum CerError {
     DescriptionUtf8(#[source] Utf8Error),
     #[error("cannot read certificate subject alternative names")]
     San(#[source] X509Error),
+    #[error("cannot read certificate extension")]
+    Extension(#[source] X509Error),
     #[error("cannot parse certificate timestamp")]
     Timestamp,
     #[error("cannot parse pfx")]
     Pfx(#[source] std::io::Error),
+    #[error("cannot export private keys")]
+    Export(#[source] std::io::Error),
     #[error("password is not a string")]
     Password(#[source] ShellError),
     #[error("cannot parse der")]
     Der(#[source] NomErr<X509Error>),
     #[error("cannot read fingerprint")]
     Fingerprint(#[source] std::io::Error),
+    #[error("cannot read file")]
+    Read(#[source] std::io::Error),
+    /// where the certificate was expected: input, file, secret or argument
+    #[error("no certificates in {0}")]
+    NoCertificate(&'static str),
+    #[error("cannot parse distinguished name {dn}: {reason}")]
+    Dn { dn: String, reason: &'static str },
+    #[cfg(feature = "ldap")]
+    #[error("cannot query ldap directory")]
+    Ldap(#[source] ldap3::LdapError),
+    #[error("missing column {0}")]
+    MissingColumn(&'static str),
+    #[error("cannot parse json")]
+    Json(#[source] serde_json::Error),
+    #[error("cannot decode base64 data")]
+    Base64(#[source] data_encoding::DecodeError),
+    #[error("invalid object identifier {0}")]
+    Oid(String),
+    #[error("san column lists {shown} of {count} subject alternative names")]
+    TruncatedSans { shown: usize, count: i64 },
+    #[error("input exceeds the limit of {limit} {what}")]
+    Limit { what: &'static str, limit: usize },
+    #[error("built without feature {0}")]
+    Feature(&'static str),
+    #[error("feature {0} is only available on windows")]
+    Platform(&'static str),
+}
+
+impl CerError {
+    /// Message including the source error, for errors reported inside a value
+    pub fn details(&self) -> String {
+        match std::error::Error::source(self) {
+            Some(source) => format!("{}: {}", self, source),
+            None => self.to_string(),
+        }
+    }
+
+    /// What the user can try next, for the failures with a known likely cause
+    pub fn suggestion(&self) -> Option<String> {
+        if let CerError::Feature(feature) = self {
+            return Some(format!("reinstall the plugin with the feature enabled, like `cargo install nu_plugin_cer --features {}`", feature));
+        }
+        let suggestion = match self {
+            CerError::Pem(_) => Some("base64 text without the -----BEGIN----- armor can be converted with `decode base64` first"),
+            CerError::Armor { .. } => Some("run without --rfc7468-strict to accept the armor leniently"),
+            CerError::Parse(_) | CerError::Der(_) => Some("the data may be a private key or a certificate request instead of a certificate"),
+            CerError::Request(_) => Some("the data may be a certificate instead of a certificate request"),
+            CerError::San(_) => Some("only DNS names and IP addresses are read by default, pass --san-types to read the other kinds into san_other"),
+            CerError::Pfx(_) => Some("check --password; binary input is read as pfx, DER certificates must be converted to PEM first"),
+            CerError::Export(_) => Some("the pfx must hold its private keys, certificates without a key are exported as they are"),
+            CerError::Password(_) => Some("pass the password as a string, like --password 'secret'"),
+            CerError::Read(_) => Some("check that the path exists and is readable"),
+            CerError::NoCertificate("input") => Some("the input must hold a PEM certificate with its -----BEGIN CERTIFICATE----- armor"),
+            CerError::NoCertificate("file") => Some("the file must hold PEM certificates, check the path the configuration points to"),
+            CerError::NoCertificate("secret") => Some("the secret value must be a base64 PEM certificate, like the tls.crt of a kubernetes.io/tls secret"),
+            CerError::NoCertificate(_) => Some("pass the PEM text of the certificate, like `(open server.pem)`"),
+            CerError::Dn { .. } => Some("escape commas, plus signs and other special characters with a backslash"),
+            #[cfg(feature = "ldap")]
+            CerError::Ldap(_) => Some("check the url scheme (ldap:// or ldaps://), the search base and the bind credentials"),
+            CerError::MissingColumn(_) => Some("the records must come from a cer command, like `cer --list`"),
+            CerError::Json(_) => Some("the file must be the output of `cer snapshot | to json`"),
+            CerError::Base64(_) => Some("the value must be base64 text without the PEM armor"),
+            CerError::Oid(_) => Some("object identifiers are dotted numbers, like 2.5.29.17"),
+            CerError::TruncatedSans { .. } => Some("read the certificates with `cer --full-sans`, so every name is matched"),
+            CerError::Limit { .. } => Some("split the input, or raise the limit in $env.config.plugins.cer.limits, like {max_certificates: 200000}"),
+            CerError::Platform(_) => Some("convert the data to PEM on a windows machine, or with `openssl pkcs12 -nokeys`"),
+            _ => None,
+        };
+        suggestion.map(String::from)
+    }
+}
+
+impl From<CoreError> for CerError {
+    fn from(value: CoreError) -> Self {
+        match value {
+            CoreError::Armor { line, reason } => CerError::Armor { line, reason },
+            CoreError::Dn { dn, reason } => CerError::Dn { dn, reason },
+            CoreError::Limit { what, limit } => CerError::Limit { what, limit },
+        }
+    }
 }
 
 impl From<CerError> for LabeledError {
     fn from(value: CerError) -> Self {
-        match &value {
+        let mut error = match &value {
             CerError::Pem(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Armor { .. } => LabeledError::new(value.to_string()),
             CerError::Parse(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Request(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
             CerError::CommonName(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Email(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
             CerError::FriendlyName(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
@@ -55,10 +154,16 @@ impl From<CerError> for LabeledError {
             CerError::San(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Extension(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
             CerError::Timestamp => LabeledError::new(value.to_string()),
             CerError::Pfx(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Export(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
             CerError::Password(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
@@ -71,6 +176,34 @@ impl From<CerError> for LabeledError {
             CerError::DescriptionUtf8(source) => {
                 LabeledError::new(value.to_string()).with_help(format!("{}", source))
             }
+            CerError::Read(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
+            CerError::NoCertificate(_) => LabeledError::new(value.to_string()),
+            CerError::Dn { .. } => LabeledError::new(value.to_string()),
+            #[cfg(feature = "ldap")]
+            CerError::Ldap(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
+            CerError::MissingColumn(_) => LabeledError::new(value.to_string()),
+            CerError::Json(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
+            CerError::Base64(source) => {
+                LabeledError::new(value.to_string()).with_help(format!("{}", source))
+            }
+            CerError::Oid(_) => LabeledError::new(value.to_string()),
+            CerError::TruncatedSans { .. } => LabeledError::new(value.to_string()),
+            CerError::Limit { .. } => LabeledError::new(value.to_string()),
+            CerError::Feature(_) => LabeledError::new(value.to_string()),
+            CerError::Platform(_) => LabeledError::new(value.to_string()),
+        };
+        if let Some(suggestion) = value.suggestion() {
+            error.help = Some(match error.help {
+                Some(help) => format!("{}\n{}", help, suggestion),
+                None => suggestion,
+            });
         }
+        error
     }
 }
@@ -0,0 +1,75 @@
+use chrono::{DateTime, FixedOffset};
+use nu_protocol::{Span, Value};
+
+const DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// The buckets without --buckets, named as `--buckets [7day 30day 90day 1yr]` would name them
+pub fn get_default_buckets(span: Span) -> Vec<(String, i64)> {
+    [
+        Value::duration(7 * DAY, span),
+        Value::duration(30 * DAY, span),
+        Value::duration(90 * DAY, span),
+        Value::string("1yr", span),
+    ]
+    .iter()
+    .filter_map(get_bucket)
+    .collect()
+}
+
+/// Index of the bucket of an expiration, 0 is expired and the last one is later
+pub fn get_bucket_index(
+    expiration: DateTime<FixedOffset>,
+    now: DateTime<FixedOffset>,
+    buckets: &[(String, i64)],
+) -> usize {
+    let remaining = (expiration - now).num_nanoseconds().unwrap_or(i64::MAX);
+    if remaining < 0 {
+        return 0;
+    }
+    buckets
+        .iter()
+        .position(|(_name, nanoseconds)| remaining <= *nanoseconds)
+        .map_or(buckets.len() + 1, |index| index + 1)
+}
+
+/// Name and length in nanoseconds of a bucket
+pub fn get_bucket(value: &Value) -> Option<(String, i64)> {
+    let bucket = match value {
+        Value::Duration { val, .. } => (get_duration_name(*val), *val),
+        Value::String { val, .. } => (val.clone(), parse_duration(val)?),
+        _ => return None,
+    };
+    (bucket.1 > 0).then_some(bucket)
+}
+
+// nushell has no year unit, so 1yr is given as a bare string
+pub fn parse_duration(duration: &str) -> Option<i64> {
+    let unit_start = duration.find(|c: char| !c.is_ascii_digit())?;
+    let (count, unit) = duration.split_at(unit_start);
+    let unit = match unit {
+        "sec" => 1_000_000_000,
+        "min" => 60 * 1_000_000_000,
+        "hr" => 60 * 60 * 1_000_000_000,
+        "day" => DAY,
+        "wk" => 7 * DAY,
+        "yr" => 365 * DAY,
+        _ => return None,
+    };
+    count.parse::<i64>().ok()?.checked_mul(unit)
+}
+
+pub fn get_duration_name(nanoseconds: i64) -> String {
+    let units = [
+        ("wk", 7 * DAY),
+        ("day", DAY),
+        ("hr", 60 * 60 * 1_000_000_000),
+        ("min", 60 * 1_000_000_000),
+        ("sec", 1_000_000_000),
+    ];
+    units
+        .into_iter()
+        .find(|(_unit, length)| nanoseconds % length == 0)
+        .map_or(format!("{}ns", nanoseconds), |(unit, length)| {
+            format!("{}{}", nanoseconds / length, unit)
+        })
+}
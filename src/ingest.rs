@@ -0,0 +1,361 @@
+use chrono::DateTime;
+use data_encoding::BASE64;
+use nu_protocol::{Record, Span, Value};
+
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::{
+    certificate::{get_certificate_ders, get_pem_values, CerResult, RecordOptions},
+    core::{
+        key::{find_private_key, key_matches},
+        spiffe::parse_spiffe_id,
+    },
+    error::CerError,
+};
+
+/// Tools whose JSON output is recognized, for the error of an unknown record
+pub const INGESTED_SOURCES: &str = "istioctl proxy-config secret, an envoy secrets config dump, a vault pki response, aws acm describe-certificate or get-certificate, or gcloud certificate-manager certificates describe";
+
+/// Certificates embedded in the JSON output of other tools, recognized by its fields.
+/// Returns None for a record of an unknown shape.
+pub fn get_ingested_values(
+    record: &Record,
+    options: &RecordOptions,
+    span: Span,
+) -> CerResult<Option<Vec<Value>>> {
+    let values = if record.contains("dynamicActiveSecrets")
+        || record.contains("staticSecrets")
+        || record.contains("configs")
+    {
+        get_envoy_values(record, options, span)?
+    } else if let Some(fields) = get_vault_fields(record) {
+        get_vault_values(fields, options, span)?
+    } else if record.contains("Certificate") {
+        get_acm_values(record, options, span)?
+    } else if record.contains("pemCertificate") {
+        get_gcp_values(record, options, span)?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(values))
+}
+
+pub fn get_path<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(value, |value, column| value.as_record().ok()?.get(*column))
+}
+
+pub fn get_text(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(|value| value.as_str().ok())
+        .map(str::to_string)
+}
+
+/// Records of the certificates of a PEM text, after the columns of where they were found
+fn get_sourced_values(
+    columns: &[(&str, Value)],
+    pem: &String,
+    options: &RecordOptions,
+    span: Span,
+) -> CerResult<Vec<Value>> {
+    let values = get_pem_values(pem, options, span)?
+        .into_iter()
+        .map(|value| {
+            let mut record = Record::new();
+            for (column, val) in columns {
+                record.push(*column, val.clone());
+            }
+            for (column, val) in value.into_record().unwrap_or_default() {
+                record.push(column, val);
+            }
+            Value::record(record, span)
+        })
+        .collect();
+    Ok(values)
+}
+
+// the SPIFFE ID is a URI SAN, read into san_other
+fn get_trust_domain(value: &Value) -> Option<String> {
+    let others = value.get_data_by_key("san_other")?.into_list().ok()?;
+    others.iter().find_map(|other| {
+        let uri = other.get_data_by_key("value")?.into_string().ok()?;
+        let (trust_domain, _path) = parse_spiffe_id(&uri).ok()?;
+        Some(trust_domain.to_string())
+    })
+}
+
+// envoy DataSource: inline_bytes are base64, inline_string is the PEM itself
+fn get_data_source(value: &Value) -> CerResult<Option<String>> {
+    if let Some(bytes) = get_text(get_path(value, &["inlineBytes"])) {
+        let pem = BASE64.decode(bytes.as_bytes()).map_err(CerError::Base64)?;
+        return Ok(Some(String::from_utf8_lossy(&pem).into_owned()));
+    }
+    Ok(get_text(get_path(value, &["inlineString"])))
+}
+
+/// Secrets of `istioctl proxy-config secret -o json` and of the envoy /config_dump secrets
+fn get_envoy_values(record: &Record, options: &RecordOptions, span: Span) -> CerResult<Vec<Value>> {
+    let dump = Value::record(record.clone(), span);
+    let configs = match record.get("configs") {
+        Some(Value::List { vals, .. }) => vals.clone(),
+        _ => vec![dump],
+    };
+    let mut values = Vec::new();
+    for config in &configs {
+        for (column, state) in [
+            ("staticSecrets", "static"),
+            ("dynamicActiveSecrets", "active"),
+            ("dynamicWarmingSecrets", "warming"),
+        ] {
+            let secrets = get_path(config, &[column])
+                .and_then(|secrets| secrets.as_list().ok())
+                .unwrap_or_default();
+            for secret in secrets {
+                let name = get_text(get_path(secret, &["name"])).unwrap_or_default();
+                for (part, path) in [
+                    (
+                        "certificate_chain",
+                        ["secret", "tlsCertificate", "certificateChain"],
+                    ),
+                    ("trusted_ca", ["secret", "validationContext", "trustedCa"]),
+                ] {
+                    let Some(source) = get_path(secret, &path) else {
+                        continue;
+                    };
+                    let Some(pem) = get_data_source(source)? else {
+                        continue;
+                    };
+                    let columns = [
+                        ("secret", Value::string(&name, span)),
+                        ("state", Value::string(state, span)),
+                        ("part", Value::string(part, span)),
+                    ];
+                    for value in get_sourced_values(&columns, &pem, options, span)? {
+                        let trust_domain = get_trust_domain(&value)
+                            .map_or(Value::nothing(span), |domain| Value::string(domain, span));
+                        let mut record = value.into_record().unwrap_or_default();
+                        record.push("trust_domain", trust_domain);
+                        values.push(Value::record(record, span));
+                    }
+                }
+            }
+        }
+    }
+    Ok(values)
+}
+
+// the fields are under data in API responses and `vault write -format=json`, at the top after `get data`
+fn get_vault_fields(record: &Record) -> Option<&Record> {
+    let fields = match record.get("data") {
+        Some(Value::Record { val, .. }) => val,
+        _ => record,
+    };
+    let vault = matches!(fields.get("certificate"), Some(Value::String { .. }))
+        && ["issuing_ca", "ca_chain", "private_key", "serial_number"]
+            .iter()
+            .any(|column| fields.contains(column));
+    vault.then_some(fields)
+}
+
+// None when there is no private key, or it cannot be compared, like an encrypted one
+fn get_vault_key_match(certificate: &str, private_key: Option<&str>) -> CerResult<Option<bool>> {
+    let Some(private_key) = private_key else {
+        return Ok(None);
+    };
+    let Some(der) = get_certificate_ders(certificate)?.into_iter().next() else {
+        return Ok(None);
+    };
+    let (_rem, cer) = X509Certificate::from_der(&der).map_err(CerError::Der)?;
+    let key = find_private_key(private_key.as_bytes());
+    Ok(key.and_then(|key| key_matches(&key, &cer)))
+}
+
+/// Parts of the response of the vault pki issue, sign and read endpoints
+fn get_vault_values(fields: &Record, options: &RecordOptions, span: Span) -> CerResult<Vec<Value>> {
+    let text = |column: &str| fields.get(column).and_then(|value| value.as_str().ok());
+    let certificate = text("certificate").unwrap_or_default().to_string();
+    let key_match = get_vault_key_match(&certificate, text("private_key"))?;
+    let mut parts = vec![("certificate", certificate)];
+    parts.extend(text("issuing_ca").map(|pem| ("issuing_ca", pem.to_string())));
+    let chain = fields
+        .get("ca_chain")
+        .and_then(|chain| chain.as_list().ok())
+        .unwrap_or_default();
+    parts.extend(
+        chain
+            .iter()
+            .filter_map(|pem| Some(("ca_chain", pem.as_str().ok()?.to_string()))),
+    );
+
+    let mut values = Vec::new();
+    for (part, pem) in parts {
+        let columns = [("part", Value::string(part, span))];
+        for value in get_sourced_values(&columns, &pem, options, span)? {
+            let key_match = match key_match {
+                Some(matches) if part == "certificate" => Value::bool(matches, span),
+                _ => Value::nothing(span),
+            };
+            let mut record = value.into_record().unwrap_or_default();
+            record.push("key_match", key_match);
+            values.push(Value::record(record, span));
+        }
+    }
+    Ok(values)
+}
+
+fn get_strings(value: Option<&Value>, field: Option<&str>, span: Span) -> Value {
+    let values = value
+        .and_then(|values| values.as_list().ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| {
+            let value = match field {
+                Some(field) => get_path(value, &[field])?,
+                None => value,
+            };
+            Some(Value::string(value.as_str().ok()?, span))
+        })
+        .collect();
+    Value::list(values, span)
+}
+
+// the aws cli writes dates as ISO 8601 in version 2, and as epoch seconds in version 1
+fn get_cloud_date(value: Option<&Value>, span: Span) -> Value {
+    let date = match value {
+        Some(Value::Date { val, .. }) => Some(*val),
+        Some(Value::String { val, .. }) => DateTime::parse_from_rfc3339(val).ok(),
+        Some(Value::Float { val, .. }) => {
+            DateTime::from_timestamp(*val as i64, 0).map(|date| date.into())
+        }
+        Some(Value::Int { val, .. }) => DateTime::from_timestamp(*val, 0).map(|date| date.into()),
+        _ => None,
+    };
+    date.map_or(Value::nothing(span), |date| Value::date(date, span))
+}
+
+/// Metadata of a cloud certificate manager, in the same columns for every provider
+struct CloudMetadata {
+    provider: &'static str,
+    resource: Option<String>,
+    kind: Option<String>,
+    status: Option<String>,
+    renewal_status: Option<String>,
+    in_use_by: Value,
+}
+
+impl CloudMetadata {
+    fn columns(&self, span: Span) -> Vec<(&'static str, Value)> {
+        let text = |text: &Option<String>| {
+            text.as_ref()
+                .map_or(Value::nothing(span), |text| Value::string(text, span))
+        };
+        vec![
+            ("provider", Value::string(self.provider, span)),
+            ("resource", text(&self.resource)),
+            ("type", text(&self.kind)),
+            ("status", text(&self.status)),
+            ("renewal_status", text(&self.renewal_status)),
+            ("in_use_by", self.in_use_by.clone()),
+        ]
+    }
+}
+
+/// `aws acm get-certificate` returns the PEM, `aws acm describe-certificate` only the metadata
+fn get_acm_values(record: &Record, options: &RecordOptions, span: Span) -> CerResult<Vec<Value>> {
+    match record.get("Certificate") {
+        Some(Value::String { val, .. }) => {
+            let metadata = CloudMetadata {
+                provider: "acm",
+                resource: None,
+                kind: None,
+                status: None,
+                renewal_status: None,
+                in_use_by: Value::nothing(span),
+            };
+            let mut values = Vec::new();
+            let chain = get_text(record.get("CertificateChain"));
+            for (part, pem) in [("certificate", Some(val.clone())), ("chain", chain)] {
+                let Some(pem) = pem else {
+                    continue;
+                };
+                let mut columns = metadata.columns(span);
+                columns.push(("part", Value::string(part, span)));
+                values.extend(get_sourced_values(&columns, &pem, options, span)?);
+            }
+            Ok(values)
+        }
+        Some(description @ Value::Record { .. }) => {
+            let text = |path: &[&str]| get_text(get_path(description, path));
+            let metadata = CloudMetadata {
+                provider: "acm",
+                resource: text(&["CertificateArn"]),
+                kind: text(&["Type"]),
+                status: text(&["Status"]),
+                renewal_status: text(&["RenewalSummary", "RenewalStatus"])
+                    .or(text(&["RenewalEligibility"])),
+                in_use_by: get_strings(get_path(description, &["InUseBy"]), None, span),
+            };
+            // the description has no PEM, its own fields fill the certificate columns
+            let mut record = Record::new();
+            for (column, value) in metadata.columns(span) {
+                record.push(column, value);
+            }
+            let cn = text(&["DomainName"])
+                .map(|cn| Value::string(cn, span))
+                .into_iter()
+                .collect();
+            record.push("cn", Value::list(cn, span));
+            record.push(
+                "san",
+                get_strings(
+                    get_path(description, &["SubjectAlternativeNames"]),
+                    None,
+                    span,
+                ),
+            );
+            record.push(
+                "expiration",
+                get_cloud_date(get_path(description, &["NotAfter"]), span),
+            );
+            Ok(vec![Value::record(record, span)])
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// `gcloud certificate-manager certificates describe --format json`
+fn get_gcp_values(record: &Record, options: &RecordOptions, span: Span) -> CerResult<Vec<Value>> {
+    let text = |column: &str| get_text(record.get(column));
+    let managed = record.get("managed");
+    let metadata = CloudMetadata {
+        provider: "gcp",
+        resource: text("name"),
+        kind: Some(
+            if managed.is_some() {
+                "managed"
+            } else {
+                "self-managed"
+            }
+            .into(),
+        ),
+        status: managed.and_then(|managed| get_text(get_path(managed, &["state"]))),
+        renewal_status: managed.and_then(|managed| {
+            let attempts = get_path(managed, &["authorizationAttemptInfo"])?
+                .as_list()
+                .ok()?;
+            // a domain that is not authorized blocks the renewal
+            let states = attempts
+                .iter()
+                .filter_map(|attempt| get_text(get_path(attempt, &["state"])))
+                .collect::<Vec<String>>();
+            states
+                .iter()
+                .find(|state| *state != "AUTHORIZED")
+                .or(states.first())
+                .cloned()
+        }),
+        in_use_by: get_strings(record.get("usedBy"), Some("name"), span),
+    };
+    let pem = text("pemCertificate").unwrap_or_default();
+    get_sourced_values(&metadata.columns(span), &pem, options, span)
+}
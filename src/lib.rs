@@ -0,0 +1,24 @@
+//! Certificate commands of nu_plugin_cer, the binary serves them to nushell.
+//! The parsing entry points are public for the fuzz targets and benches.
+
+mod catalog;
+pub mod certificate;
+mod command;
+mod core;
+mod coverage;
+mod digest;
+mod error;
+mod expiry;
+mod ingest;
+mod lint;
+mod merge;
+pub mod pfx;
+pub mod plugin;
+mod policy;
+mod preset;
+mod provenance;
+mod redact;
+mod scan;
+mod secret;
+#[cfg(test)]
+mod tests;
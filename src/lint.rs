@@ -0,0 +1,162 @@
+use nu_protocol::Value;
+
+use crate::policy::{get_policy, Policy};
+
+/// English template of a finding, translated with the message catalog, and its arguments
+pub type Finding = (&'static str, Vec<String>);
+
+/// Thresholds of a compliance regime or of the trust policy
+pub enum Profile {
+    Regime(LintProfile),
+    Policy(Policy),
+}
+
+/// Thresholds of a compliance regime, a missing setting is not checked
+pub struct LintProfile {
+    pub min_rsa_bits: i64,
+    pub algorithms: Option<Vec<String>>,
+    pub hashes: Option<Vec<String>>,
+}
+
+fn get_strings(strings: &[&str]) -> Option<Vec<String>> {
+    Some(strings.iter().map(|string| string.to_string()).collect())
+}
+
+/// A built-in regime, the trust policy or a profile of the `lint_profiles` in the plugin config
+pub fn get_profile(config: Option<&Value>, name: &str) -> Option<Profile> {
+    let profile = match name {
+        "policy" => return get_policy(config).map(Profile::Policy),
+        // CA/Browser Forum Baseline Requirements section 6.1.5 and 7.1.3
+        "cabf" => LintProfile {
+            min_rsa_bits: 2048,
+            algorithms: get_strings(&["rsa", "rsa-pss", "ec-p256", "ec-p384", "ec-p521"]),
+            hashes: get_strings(&["sha256", "sha384", "sha512"]),
+        },
+        // FIPS 186-5 and NIST SP 800-131A
+        "fips" => LintProfile {
+            min_rsa_bits: 2048,
+            algorithms: get_strings(&[
+                "rsa", "rsa-pss", "ec-p256", "ec-p384", "ec-p521", "ed25519", "ed448",
+            ]),
+            hashes: get_strings(&["sha224", "sha256", "sha384", "sha512"]),
+        },
+        // the classical algorithms CNSA 2.0 still allows until the transition to ML-DSA
+        "cnsa2.0" => LintProfile {
+            min_rsa_bits: 3072,
+            algorithms: get_strings(&["rsa", "rsa-pss", "ec-p384"]),
+            hashes: get_strings(&["sha384", "sha512"]),
+        },
+        _ => {
+            let profile = config?
+                .get_data_by_key("lint_profiles")?
+                .get_data_by_key(name)?;
+            let list = |column: &str| {
+                profile.get_data_by_key(column).map(|list| {
+                    list.into_list()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|value| value.into_string().ok())
+                        .collect()
+                })
+            };
+            LintProfile {
+                min_rsa_bits: profile
+                    .get_data_by_key("min_rsa_bits")
+                    .and_then(|bits| bits.as_int().ok())
+                    .unwrap_or_default(),
+                algorithms: list("algorithms"),
+                hashes: list("hashes"),
+            }
+        }
+    };
+    Some(Profile::Regime(profile))
+}
+
+pub fn check_profile(
+    profile: &LintProfile,
+    algorithm: &str,
+    size: i64,
+    hash: &str,
+    issues: &mut Vec<Finding>,
+) {
+    if let Some(algorithms) = &profile.algorithms {
+        if !algorithms.iter().any(|allowed| allowed == algorithm) {
+            issues.push((
+                "{0} keys are not allowed by the profile",
+                vec![algorithm.into()],
+            ));
+        }
+    }
+    if algorithm.starts_with("rsa") && size < profile.min_rsa_bits {
+        issues.push((
+            "{0} key of {1} bits is shorter than the {2} bits of the profile",
+            vec![
+                algorithm.into(),
+                size.to_string(),
+                profile.min_rsa_bits.to_string(),
+            ],
+        ));
+    }
+    if let Some(hashes) = &profile.hashes {
+        if !hashes.iter().any(|allowed| allowed == hash) {
+            issues.push((
+                "{0} signatures are not allowed by the profile",
+                vec![hash.into()],
+            ));
+        }
+    }
+}
+
+/// Consistency of the key usage with the key algorithm, the extended key usage and the CA flag
+pub fn check_key_usage(
+    algorithm: &str,
+    key_usage: &[String],
+    eku: &[String],
+    ca: bool,
+) -> Vec<Finding> {
+    let has = |usage: &str| key_usage.iter().any(|name| name == usage);
+    let mut issues = Vec::new();
+
+    // RSA keys transport keys, EC keys agree on them, the other algorithms can only sign
+    let rsa = algorithm.starts_with("rsa");
+    let ec = algorithm.starts_with("ec");
+    for usage in ["keyEncipherment", "dataEncipherment"] {
+        if has(usage) && !rsa {
+            issues.push((
+                "{0} is not possible with {1} keys",
+                vec![usage.into(), algorithm.into()],
+            ));
+        }
+    }
+    if has("keyAgreement") && !ec {
+        issues.push((
+            "{0} is not possible with {1} keys",
+            vec!["keyAgreement".into(), algorithm.into()],
+        ));
+    }
+    if (has("encipherOnly") || has("decipherOnly")) && !has("keyAgreement") {
+        issues.push(("encipherOnly and decipherOnly require keyAgreement", vec![]));
+    }
+
+    // the extension is optional, purposes are only checked against it when present
+    if !key_usage.is_empty() {
+        for purpose in [
+            "serverAuth",
+            "clientAuth",
+            "codeSigning",
+            "timeStamping",
+            "OCSPSigning",
+        ] {
+            if eku.iter().any(|name| name == purpose) && !has("digitalSignature") {
+                issues.push(("{0} without digitalSignature", vec![purpose.into()]));
+            }
+        }
+        if ca && !has("keyCertSign") {
+            issues.push(("certificate authority without keyCertSign", vec![]));
+        }
+    }
+    if !ca && has("keyCertSign") {
+        issues.push(("keyCertSign without the basic constraints CA flag", vec![]));
+    }
+    issues
+}
@@ -1,10 +1,5 @@
-mod certificate;
-mod command;
-mod error;
-mod plugin;
-
 use nu_plugin::{serve_plugin, JsonSerializer};
-use plugin::CerPlugin;
+use nu_plugin_cer::plugin::CerPlugin;
 
 // cargo build --release
 // mkdir ($env.APPDATA | path join "nushell\\plugins")
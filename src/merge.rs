@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use nu_protocol::{Record, Span, Value};
+
+use crate::{certificate::CerResult, error::CerError};
+
+/// Columns of a merged row that say where it comes from, never compared between sources
+const MERGE_COLUMNS: [&str; 2] = ["sources", "conflicts"];
+
+/// Columns the rows were matched by, whose values only differ in case
+const FINGERPRINT_COLUMNS: [&str; 2] = ["sha256", "thumbprint"];
+
+/// Merges named tables, in order of priority, into one row per certificate.
+/// Rows are matched by sha256, or by thumbprint when one of them only has a thumbprint,
+/// like the rows of store snapshots.
+pub fn merge_tables(tables: &Record, span: Span) -> CerResult<Vec<Value>> {
+    let mut rows = Vec::new();
+    for (source, table) in tables.iter() {
+        let table = table
+            .as_list()
+            .map_err(|_err| CerError::MissingColumn("sha256"))?;
+        for row in table {
+            let record = row
+                .as_record()
+                .map_err(|_err| CerError::MissingColumn("sha256"))?;
+            let fingerprint = |column: &str| {
+                record
+                    .get(column)
+                    .and_then(|value| value.as_str().ok())
+                    .map(str::to_lowercase)
+            };
+            rows.push((
+                source,
+                record,
+                fingerprint("sha256"),
+                fingerprint("thumbprint"),
+            ));
+        }
+    }
+    // the rows with both fingerprints tell which sha256 a thumbprint-only row has
+    let sha256s = rows
+        .iter()
+        .filter_map(|(_source, _record, sha256, thumbprint)| {
+            Some((thumbprint.clone()?, sha256.clone()?))
+        })
+        .collect::<HashMap<String, String>>();
+
+    let mut merged: Vec<Record> = Vec::new();
+    let mut indexes = HashMap::new();
+    let mut unmerged = Vec::new();
+    for (source, record, sha256, thumbprint) in rows {
+        let key = match (sha256, thumbprint) {
+            (Some(sha256), _) => sha256,
+            (None, Some(thumbprint)) => match sha256s.get(&thumbprint) {
+                Some(sha256) => sha256.clone(),
+                None => format!("thumbprint:{}", thumbprint),
+            },
+            (None, None) => {
+                unmerged.push(Value::record(new_row(source, record, span), span));
+                continue;
+            }
+        };
+        match indexes.get(&key) {
+            Some(&index) => merge_row(&mut merged[index], source, record, span),
+            None => {
+                indexes.insert(key, merged.len());
+                merged.push(new_row(source, record, span));
+            }
+        }
+    }
+    let mut values = merged
+        .into_iter()
+        .map(|row| Value::record(row, span))
+        .collect::<Vec<Value>>();
+    values.extend(unmerged);
+    Ok(values)
+}
+
+fn new_row(source: &str, record: &Record, span: Span) -> Record {
+    let mut row = record.clone();
+    // merge output merged again names its table as the only source
+    row.insert(
+        "sources",
+        Value::list(vec![Value::string(source, span)], span),
+    );
+    row.insert("conflicts", Value::list(Vec::new(), span));
+    row
+}
+
+// the first source keeps its values, the columns only later sources have are added,
+// and each value that was left out is listed with its source
+fn merge_row(row: &mut Record, source: &str, record: &Record, span: Span) {
+    let mut conflicts = Vec::new();
+    for (column, value) in record.iter() {
+        if matches!(value, Value::Nothing { .. }) || MERGE_COLUMNS.contains(&column.as_str()) {
+            continue;
+        }
+        match row.get(column) {
+            None | Some(Value::Nothing { .. }) => {
+                row.insert(column.clone(), value.clone());
+            }
+            Some(kept) if kept != value && !FINGERPRINT_COLUMNS.contains(&column.as_str()) => {
+                let mut conflict = Record::new();
+                conflict.push("column", Value::string(column, span));
+                conflict.push("source", Value::string(source, span));
+                conflict.push("value", value.clone());
+                conflicts.push(Value::record(conflict, span));
+            }
+            Some(_) => {}
+        }
+    }
+    for (column, new) in [
+        ("sources", vec![Value::string(source, span)]),
+        ("conflicts", conflicts),
+    ] {
+        if let Some(Value::List { vals, .. }) = row.get_mut(column) {
+            vals.extend(new);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(all(windows, feature = "pkcs12"))]
+use nu_protocol::Record;
+use nu_protocol::{Span, Value};
+#[cfg(all(windows, feature = "pkcs12"))]
+use schannel::{
+    cert_context::CertContext,
+    cert_store::{CertStore, PfxImportOptions},
+};
+#[cfg(all(windows, feature = "pkcs12"))]
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::certificate::{CerResult, RecordOptions};
+#[cfg(all(windows, feature = "pkcs12"))]
+use crate::{
+    certificate::{get_der_thumbprint, get_record, get_sha256, get_value_size},
+    core::{
+        key::{get_key_algorithm, get_key_size},
+        limits::check_limit,
+    },
+    error::CerError,
+};
+
+// PFX files are imported with the Windows CryptoAPI, other platforms only read PEM input.
+// Private keys are never returned, key_info adds the algorithm and size of each key.
+#[cfg(all(windows, feature = "pkcs12"))]
+pub fn get_pfx_values(
+    data: &[u8],
+    password: Option<&str>,
+    key_info: bool,
+    options: &RecordOptions,
+    span: Span,
+) -> CerResult<Vec<Value>> {
+    let store = import_pfx(data, password)?;
+    let mut output_size = 0;
+    let values = store
+        .certs()
+        .enumerate()
+        .map(|(index, cer)| {
+            check_limit("certificates", index + 1, options.limits.max_certificates)?;
+            let der = cer.to_der();
+            let (_rem, pem) = X509Certificate::from_der(der).map_err(CerError::Der)?;
+            let mut record = get_record(&pem, options, span)?;
+            record.push(
+                "friendly",
+                Value::string(get_pfx_friendly_name(&cer)?, span),
+            );
+            // the SHA-1 of the DER is the thumbprint CryptoAPI would compute for it
+            record.push("thumbprint", get_der_thumbprint(der, span));
+            record.push("sha256", get_sha256(der, span));
+            if key_info {
+                record.push("private_key", get_pfx_key_info(&cer, &pem, span));
+            }
+            let value = Value::record(record, span);
+            output_size += get_value_size(&value);
+            check_limit(
+                "bytes of output",
+                output_size,
+                options.limits.max_output_size,
+            )?;
+            Ok(value)
+        })
+        .collect::<Result<Vec<Value>, CerError>>()?;
+    Ok(values)
+}
+
+/// Imports a PFX into a memory store of its own, so concurrent calls share no store context.
+/// The private keys are ephemeral and freed with the store, they never reach a key container.
+/// Extended properties are not imported, as they can point a certificate at a persisted key.
+#[cfg(all(windows, feature = "pkcs12"))]
+fn import_pfx(data: &[u8], password: Option<&str>) -> CerResult<CertStore> {
+    let mut pfx = PfxImportOptions::new();
+    pfx.no_persist_key(true)
+        .include_extended_properties(false)
+        .exportable_private_key(false)
+        .machine_keyset(false);
+    if let Some(password) = password {
+        pfx.password(password);
+    }
+    pfx.import(data).map_err(CerError::Pfx)
+}
+
+/// Exports the certificates and private keys of a PFX as a new PFX encrypted with the passphrase.
+/// The keys are imported exportable but ephemeral, so they are never persisted either.
+#[cfg(all(windows, feature = "pkcs12"))]
+pub fn export_pfx(data: &[u8], password: Option<&str>, passphrase: &str) -> CerResult<Vec<u8>> {
+    let mut pfx = PfxImportOptions::new();
+    pfx.no_persist_key(true)
+        .include_extended_properties(false)
+        .exportable_private_key(true)
+        .machine_keyset(false);
+    if let Some(password) = password {
+        pfx.password(password);
+    }
+    let store = pfx.import(data).map_err(CerError::Pfx)?;
+    store.export_pkcs12(passphrase).map_err(CerError::Export)
+}
+
+#[cfg(not(all(windows, feature = "pkcs12")))]
+pub fn export_pfx(_data: &[u8], _password: Option<&str>, _passphrase: &str) -> CerResult<Vec<u8>> {
+    #[cfg(feature = "pkcs12")]
+    return Err(crate::error::CerError::Platform("pkcs12"));
+    #[cfg(not(feature = "pkcs12"))]
+    return Err(crate::error::CerError::Feature("pkcs12"));
+}
+
+#[cfg(not(all(windows, feature = "pkcs12")))]
+pub fn get_pfx_values(
+    _data: &[u8],
+    _password: Option<&str>,
+    _key_info: bool,
+    _options: &RecordOptions,
+    _span: Span,
+) -> CerResult<Vec<Value>> {
+    #[cfg(feature = "pkcs12")]
+    return Err(crate::error::CerError::Platform("pkcs12"));
+    #[cfg(not(feature = "pkcs12"))]
+    return Err(crate::error::CerError::Feature("pkcs12"));
+}
+
+/// Algorithm and size of the private key of a certificate, or nothing when it has none
+#[cfg(all(windows, feature = "pkcs12"))]
+fn get_pfx_key_info(cer: &CertContext, certificate: &X509Certificate, span: Span) -> Value {
+    // compare_key checks the key against the certificate, so it has the algorithm of the public key
+    let key = cer.private_key().silent(true).compare_key(true).acquire();
+    if key.is_err() {
+        return Value::nothing(span);
+    }
+    let mut record = Record::new();
+    record.push(
+        "algorithm",
+        Value::string(get_key_algorithm(certificate.public_key()), span),
+    );
+    record.push(
+        "size",
+        Value::int(get_key_size(certificate.public_key()) as i64, span),
+    );
+    Value::record(record, span)
+}
+
+#[cfg(all(windows, feature = "pkcs12"))]
+pub fn get_pfx_friendly_name(cer: &CertContext) -> CerResult<String> {
+    cer.friendly_name().map_err(CerError::FriendlyName)
+}
@@ -1,6 +1,13 @@
-use nu_plugin::{Plugin, PluginCommand};
+use nu_plugin::{EngineInterface, EvaluatedCall, Plugin, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Example, LabeledError, Signature, Value};
 
-use crate::command::Cer;
+use crate::catalog::Catalog;
+use crate::command::{
+    Cer, CerCertManager, CerCompareStores, CerCoverage, CerCsrCompare, CerCsrVerify, CerDedup,
+    CerDn, CerExpiry, CerExplainOid, CerKeyExport, CerKeyStrength, CerKube, CerLdap, CerLint,
+    CerMerge, CerPolicyCheck, CerScanConfig, CerSnapshot, CerSnapshotDiff, CerSnapshotVerify,
+    CerSpiffeVerify, CerStrength,
+};
 
 pub struct CerPlugin;
 
@@ -10,6 +17,74 @@ impl Plugin for CerPlugin {
     }
 
     fn commands(&self) -> Vec<Box<dyn PluginCommand<Plugin = Self>>> {
-        vec![Box::new(Cer)]
+        vec![
+            Box::new(Localized(Cer)),
+            Box::new(Localized(CerScanConfig)),
+            Box::new(Localized(CerKube)),
+            Box::new(Localized(CerCsrVerify)),
+            Box::new(Localized(CerCsrCompare)),
+            Box::new(Localized(CerDn)),
+            Box::new(Localized(CerLdap)),
+            Box::new(Localized(CerSnapshot)),
+            Box::new(Localized(CerSnapshotDiff)),
+            Box::new(Localized(CerSnapshotVerify)),
+            Box::new(Localized(CerDedup)),
+            Box::new(Localized(CerExplainOid)),
+            Box::new(Localized(CerLint)),
+            Box::new(Localized(CerPolicyCheck)),
+            Box::new(Localized(CerStrength)),
+            Box::new(Localized(CerKeyStrength)),
+            Box::new(Localized(CerKeyExport)),
+            Box::new(Localized(CerSpiffeVerify)),
+            Box::new(Localized(CerCertManager)),
+            Box::new(Localized(CerMerge)),
+            Box::new(Localized(CerCompareStores)),
+            Box::new(Localized(CerExpiry)),
+            Box::new(Localized(CerCoverage)),
+        ]
+    }
+}
+
+/// Translates the help of the errors of a command with the message catalog of the plugin config
+struct Localized<C>(C);
+
+impl<C: SimplePluginCommand<Plugin = CerPlugin>> SimplePluginCommand for Localized<C> {
+    type Plugin = CerPlugin;
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn signature(&self) -> Signature {
+        self.0.signature()
+    }
+
+    fn usage(&self) -> &str {
+        self.0.usage()
+    }
+
+    fn extra_usage(&self) -> &str {
+        self.0.extra_usage()
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        self.0.search_terms()
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        self.0.examples()
+    }
+
+    fn run(
+        &self,
+        plugin: &CerPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        self.0.run(plugin, engine, call, input).map_err(|error| {
+            let config = engine.get_plugin_config().ok().flatten();
+            Catalog::from_config(config.as_ref()).localize(error)
+        })
     }
 }
@@ -0,0 +1,215 @@
+use nu_protocol::Value;
+use x509_parser::certificate::X509Certificate;
+
+use crate::{
+    certificate::{get_attribute_text, get_eku_names},
+    core::{
+        filter::glob_matches,
+        key::{get_key_algorithm, get_key_size, get_signature_hash},
+    },
+    error::CerError,
+};
+
+/// Declarative trust policy of the plugin config, a missing setting is not checked
+pub struct Policy {
+    pub allowed_issuers: Option<Vec<String>>,
+    /// minimum size in bits by key algorithm prefix, like rsa or ec
+    pub min_key_sizes: Vec<(String, i64)>,
+    pub max_validity_days: Option<i64>,
+    pub required_ekus: Vec<String>,
+    /// key algorithms and signature hashes
+    pub banned_algorithms: Vec<String>,
+}
+
+/// Reads $env.config.plugins.cer.policy
+pub fn get_policy(config: Option<&Value>) -> Option<Policy> {
+    let policy = config?.get_data_by_key("policy")?;
+    let list = |column: &str| {
+        policy.get_data_by_key(column).map(|list| {
+            list.into_list()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|value| value.into_string().ok())
+                .collect::<Vec<String>>()
+        })
+    };
+    let min_key_sizes = policy
+        .get_data_by_key("min_key_sizes")
+        .and_then(|sizes| sizes.into_record().ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|(algorithm, size)| Some((algorithm, size.as_int().ok()?)))
+        .collect();
+    Some(Policy {
+        allowed_issuers: list("allowed_issuers"),
+        min_key_sizes,
+        max_validity_days: policy
+            .get_data_by_key("max_validity_days")
+            .and_then(|days| days.as_int().ok()),
+        required_ekus: list("required_ekus").unwrap_or_default(),
+        banned_algorithms: list("banned_algorithms").unwrap_or_default(),
+    })
+}
+
+/// What a policy is checked against, None when the input does not tell
+#[derive(Default)]
+pub struct PolicyFacts {
+    pub issuers: Option<Vec<String>>,
+    pub algorithm: Option<String>,
+    pub size: Option<i64>,
+    pub hash: Option<String>,
+    pub ca: Option<bool>,
+    pub validity_days: Option<i64>,
+    pub ekus: Option<Vec<String>>,
+}
+
+/// English template of a violation, translated with the message catalog, and its arguments
+pub type Violation = (&'static str, Vec<String>);
+
+impl PolicyFacts {
+    pub fn from_certificate(cer: &X509Certificate) -> Result<PolicyFacts, CerError> {
+        let mut issuers = vec![cer.issuer().to_string()];
+        for cn in cer.issuer().iter_common_name() {
+            issuers.push(get_attribute_text(cn).map_err(CerError::CommonName)?);
+        }
+        let validity = cer.validity();
+        let ekus = cer
+            .extended_key_usage()
+            .map_err(CerError::Extension)?
+            .map(|eku| get_eku_names(eku.value))
+            .unwrap_or_default();
+        Ok(PolicyFacts {
+            issuers: Some(issuers),
+            algorithm: Some(get_key_algorithm(cer.public_key())),
+            size: Some(get_key_size(cer.public_key()) as i64),
+            hash: Some(get_signature_hash(&cer.signature_algorithm)),
+            ca: Some(cer.is_ca()),
+            validity_days: Some(
+                (validity.not_after.timestamp() - validity.not_before.timestamp()) / 86400,
+            ),
+            ekus: Some(ekus),
+        })
+    }
+
+    /// Facts of the columns of a record of another cer command, like `cer --preset security`
+    /// or `cer lint`. Records keep no validity start, so the validity is never known.
+    pub fn from_record(record: &Value) -> PolicyFacts {
+        let text = |column: &str| {
+            record
+                .get_data_by_key(column)
+                .and_then(|value| value.into_string().ok())
+        };
+        let strings = |column: &str| {
+            record
+                .get_data_by_key(column)
+                .and_then(|value| value.into_list().ok())
+                .map(|values| {
+                    values
+                        .into_iter()
+                        .filter_map(|value| value.into_string().ok())
+                        .collect::<Vec<String>>()
+                })
+        };
+        let mut issuers = strings("ca").unwrap_or_default();
+        issuers.extend(text("ca_subject"));
+        PolicyFacts {
+            issuers: (!issuers.is_empty()).then_some(issuers),
+            algorithm: text("key_algorithm"),
+            size: record
+                .get_data_by_key("key_size")
+                .and_then(|size| size.as_int().ok()),
+            hash: text("signature_hash"),
+            // only CAs sign certificates, the key_usage column of `cer lint` tells them apart
+            ca: strings("key_usage")
+                .map(|usages| usages.iter().any(|usage| usage == "keyCertSign")),
+            validity_days: None,
+            ekus: strings("eku"),
+        }
+    }
+
+    /// Violations of the policy, and the settings the facts are missing for
+    pub fn check(&self, policy: &Policy) -> (Vec<Violation>, Vec<&'static str>) {
+        let mut violations = Vec::new();
+        let mut unchecked = Vec::new();
+        if let Some(allowed) = &policy.allowed_issuers {
+            match &self.issuers {
+                Some(issuers) => {
+                    let allows = allowed
+                        .iter()
+                        .any(|pattern| issuers.iter().any(|name| glob_matches(pattern, name)));
+                    if !allows {
+                        violations.push((
+                            "issuer {0} is not allowed by the policy",
+                            vec![issuers.join(", ")],
+                        ));
+                    }
+                }
+                None => unchecked.push("allowed_issuers"),
+            }
+        }
+        if !policy.min_key_sizes.is_empty() {
+            match (&self.algorithm, self.size) {
+                (Some(algorithm), Some(size)) => {
+                    for (family, min) in &policy.min_key_sizes {
+                        if algorithm.starts_with(family.as_str()) && size < *min {
+                            violations.push((
+                                "{0} key of {1} bits is shorter than the {2} bits of the policy",
+                                vec![algorithm.clone(), size.to_string(), min.to_string()],
+                            ));
+                        }
+                    }
+                }
+                _ => unchecked.push("min_key_sizes"),
+            }
+        }
+        if !policy.banned_algorithms.is_empty() {
+            if self.algorithm.is_none() || self.hash.is_none() {
+                unchecked.push("banned_algorithms");
+            }
+            for banned in [&self.algorithm, &self.hash].into_iter().flatten() {
+                if policy.banned_algorithms.contains(banned) {
+                    violations.push(("{0} is banned by the policy", vec![banned.clone()]));
+                }
+            }
+        }
+
+        // CAs are trusted for longer and rarely restrict their purposes
+        match self.ca {
+            Some(true) => return (violations, unchecked),
+            Some(false) => {}
+            None => {
+                if policy.max_validity_days.is_some() {
+                    unchecked.push("max_validity_days");
+                }
+                if !policy.required_ekus.is_empty() {
+                    unchecked.push("required_ekus");
+                }
+                return (violations, unchecked);
+            }
+        }
+        if let Some(max) = policy.max_validity_days {
+            match self.validity_days {
+                Some(days) if days > max => violations.push((
+                    "validity of {0} days is longer than the {1} days of the policy",
+                    vec![days.to_string(), max.to_string()],
+                )),
+                Some(_days) => {}
+                None => unchecked.push("max_validity_days"),
+            }
+        }
+        if !policy.required_ekus.is_empty() {
+            match &self.ekus {
+                Some(ekus) => {
+                    for required in &policy.required_ekus {
+                        if !ekus.contains(required) {
+                            violations
+                                .push(("{0} is required by the policy", vec![required.clone()]));
+                        }
+                    }
+                }
+                None => unchecked.push("required_ekus"),
+            }
+        }
+        (violations, unchecked)
+    }
+}
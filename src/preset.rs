@@ -0,0 +1,75 @@
+use nu_protocol::{Record, Value};
+
+/// Columns of the key and signature strength, only computed when a preset asks for them
+pub const KEY_COLUMNS: [&str; 4] = [
+    "key_algorithm",
+    "key_size",
+    "signature_hash",
+    "security_bits",
+];
+
+/// Columns of a preset, from the plugin config, like
+/// $env.config.plugins.cer.presets = {inventory: [cn san expiration sha256 source]},
+/// or of the built-in presets
+pub fn get_preset(config: Option<&Value>, name: &str) -> Option<Vec<String>> {
+    let configured = config
+        .and_then(|config| config.get_data_by_key("presets")?.get_data_by_key(name))
+        .and_then(|columns| columns.into_list().ok())
+        .map(|columns| {
+            columns
+                .into_iter()
+                .filter_map(|column| column.into_string().ok())
+                .collect()
+        });
+    if configured.is_some() {
+        return configured;
+    }
+    let columns: &[&str] = match name {
+        // what an estate inventory joins and sorts by
+        "inventory" => &["cn", "san", "ca", "expiration", "sha256"],
+        // what a security review ranks by
+        "security" => &[
+            "cn",
+            "ca",
+            "key_algorithm",
+            "key_size",
+            "signature_hash",
+            "security_bits",
+            "expiration",
+        ],
+        // what matching a chain and a handshake needs
+        "tls-debug" => &[
+            "cn",
+            "san",
+            "subject",
+            "ca_subject",
+            "expiration",
+            "thumbprint",
+            "sha256",
+        ],
+        _ => return None,
+    };
+    Some(columns.iter().map(|column| column.to_string()).collect())
+}
+
+/// Keeps the columns of a preset in its order, the columns a record lacks are empty
+pub fn select_columns(value: Value, columns: &[String]) -> Value {
+    let span = value.span();
+    match value {
+        Value::List { vals, .. } => Value::list(
+            vals.into_iter()
+                .map(|val| select_columns(val, columns))
+                .collect(),
+            span,
+        ),
+        Value::Record { val, .. } => {
+            let mut record = Record::new();
+            for column in columns {
+                let value = val.get(column).cloned().unwrap_or(Value::nothing(span));
+                record.push(column, value);
+            }
+            Value::record(record, span)
+        }
+        value => value,
+    }
+}
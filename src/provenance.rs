@@ -0,0 +1,45 @@
+use nu_protocol::{Record, Value};
+
+/// Where the value of a column comes from: extracted as written in the certificate,
+/// computed from it by fixed rules, heuristic when the plugin picks among fallbacks,
+/// or input when it was read from the records or store around the certificate
+pub fn get_provenance(column: &str) -> &'static str {
+    match column {
+        "cn" | "subject" | "subject_rdns" | "san" | "san_other" | "ca" | "ca_subject"
+        | "ca_subject_rdns" | "expiration" | "email_addresses" | "signer_name"
+        | "lifetime_signing" | "key_algorithm" | "key_size" | "signature_hash" => "extracted",
+        "sha256"
+        | "thumbprint"
+        | "san_count"
+        | "san_truncated"
+        | "security_bits"
+        | "timestamping_allowed"
+        | "analyzed_at"
+        | "part"
+        | "key_match"
+        | "trust_domain" => "computed",
+        // the SPIFFE ID, then the UPN, then the common name
+        "identity" | "identity_type" => "heuristic",
+        _ => "input",
+    }
+}
+
+/// Adds a provenance column with the origin of every other column
+pub fn add_provenance(value: Value) -> Value {
+    let span = value.span();
+    match value {
+        Value::List { vals, .. } => {
+            Value::list(vals.into_iter().map(add_provenance).collect(), span)
+        }
+        Value::Record { val, .. } => {
+            let mut record = val.into_owned();
+            let provenance = record
+                .columns()
+                .map(|column| (column.clone(), Value::string(get_provenance(column), span)))
+                .collect::<Record>();
+            record.push("provenance", Value::record(provenance, span));
+            Value::record(record, span)
+        }
+        value => value,
+    }
+}
@@ -0,0 +1,230 @@
+use std::net::IpAddr;
+
+use data_encoding::HEXLOWER;
+use nu_protocol::{Record, Value};
+use sha2::{Digest, Sha256};
+
+use crate::core::{dn::normalize_name, filter::glob_matches};
+
+/// Attributes of a distinguished name that identify a person: the serialNumber, a registration
+/// or personal number, and the emailAddress
+const DN_PERSONAL: [&str; 2] = ["2.5.4.5", "1.2.840.113549.1.9.1"];
+
+fn is_personal(kind: &str) -> bool {
+    matches!(
+        normalize_name(kind).as_str(),
+        "SERIALNUMBER" | "EMAILADDRESS"
+    )
+}
+
+fn is_email(text: &str) -> bool {
+    text.contains('@')
+}
+
+/// What --redact removes or hashes, from the plugin config, like
+/// $env.config.plugins.cer.redact = {internal: ['*.example.net'], strip: [thumbprint], salt: 's3cret'}
+pub struct RedactPolicy {
+    /// globs of the hostnames to hash wherever they appear, besides private IP addresses
+    pub internal: Vec<String>,
+    /// columns whose values are always hashed
+    pub hash: Vec<String>,
+    /// columns that are removed
+    pub strip: Vec<String>,
+    /// prefix of the hashed values, so they cannot be looked up in a table of known names
+    pub salt: String,
+}
+
+impl Default for RedactPolicy {
+    fn default() -> Self {
+        let strings = |strings: &[&str]| strings.iter().map(|string| string.to_string()).collect();
+        RedactPolicy {
+            internal: strings(&[
+                "*.internal",
+                "*.local",
+                "*.localdomain",
+                "*.corp",
+                "*.lan",
+                "*.home.arpa",
+                "localhost",
+            ]),
+            hash: strings(&["email_addresses", "serial_number"]),
+            strip: Vec::new(),
+            salt: String::new(),
+        }
+    }
+}
+
+/// Reads the policy of the plugin config, the settings it leaves out keep their default
+pub fn get_redact_policy(config: Option<&Value>) -> RedactPolicy {
+    let mut policy = RedactPolicy::default();
+    let Some(config) = config.and_then(|config| config.get_data_by_key("redact")) else {
+        return policy;
+    };
+    let strings = |column: &str| {
+        config.get_data_by_key(column).map(|strings| {
+            strings
+                .into_list()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|string| string.into_string().ok())
+                .collect()
+        })
+    };
+    if let Some(internal) = strings("internal") {
+        policy.internal = internal;
+    }
+    if let Some(hash) = strings("hash") {
+        policy.hash = hash;
+    }
+    if let Some(strip) = strings("strip") {
+        policy.strip = strip;
+    }
+    if let Some(salt) = config.get_data_by_key("salt") {
+        policy.salt = salt.into_string().unwrap_or_default();
+    }
+    policy
+}
+
+impl RedactPolicy {
+    fn hash(&self, text: &str) -> String {
+        let digest = Sha256::digest(format!("{}{}", self.salt, text));
+        format!("redacted:{}", &HEXLOWER.encode(&digest)[..16])
+    }
+
+    fn is_internal(&self, name: &str) -> bool {
+        match name.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+            // unique local fc00::/7 and link-local fe80::/10
+            Ok(IpAddr::V6(ip)) => {
+                ip.is_loopback()
+                    || (ip.segments()[0] & 0xfe00) == 0xfc00
+                    || (ip.segments()[0] & 0xffc0) == 0xfe80
+            }
+            Err(_) => self
+                .internal
+                .iter()
+                .any(|pattern| glob_matches(pattern, name)),
+        }
+    }
+
+    // distinguished names have their internal, email and serialNumber values hashed, like CN=db.corp
+    fn redact_name(&self, text: &str) -> String {
+        if self.is_internal(text) {
+            return self.hash(text);
+        }
+        if !text.contains('=') {
+            return if is_email(text) {
+                self.hash(text)
+            } else {
+                text.to_string()
+            };
+        }
+        text.split(", ")
+            .map(|attribute| match attribute.split_once('=') {
+                Some((kind, value))
+                    if self.is_internal(value) || is_email(value) || is_personal(kind) =>
+                {
+                    format!("{}={}", kind, self.hash(value))
+                }
+                _ => attribute.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn redact_names(&self, value: Value) -> Value {
+        let span = value.span();
+        match value {
+            Value::String { val, .. } => Value::string(self.redact_name(&val), span),
+            Value::List { vals, .. } => Value::list(
+                vals.into_iter().map(|val| self.redact_names(val)).collect(),
+                span,
+            ),
+            Value::Record { val, .. } => {
+                let record = val.into_owned();
+                let personal = record
+                    .get("oid")
+                    .and_then(|oid| oid.as_str().ok())
+                    .is_some_and(|oid| DN_PERSONAL.contains(&oid));
+                if !personal {
+                    return Value::record(self.redact_record(record, false), span);
+                }
+                // an attribute of subject_rdns
+                let record = record
+                    .into_iter()
+                    .map(|(column, value)| match column.as_str() {
+                        "value" => (column, self.hash_all(value)),
+                        _ => (column, value),
+                    })
+                    .collect();
+                Value::record(record, span)
+            }
+            value => value,
+        }
+    }
+
+    fn hash_all(&self, value: Value) -> Value {
+        let span = value.span();
+        match value {
+            Value::String { val, .. } => Value::string(self.hash(&val), span),
+            Value::List { vals, .. } => Value::list(
+                vals.into_iter().map(|val| self.hash_all(val)).collect(),
+                span,
+            ),
+            value => value,
+        }
+    }
+
+    fn redact_record(&self, record: Record, identity_upn: bool) -> Record {
+        record
+            .into_iter()
+            .filter(|(column, _value)| !self.strip.contains(column))
+            .map(|(column, value)| {
+                let value = if self.hash.contains(&column) || (column == "identity" && identity_upn)
+                {
+                    self.hash_all(value)
+                } else {
+                    match column.as_str() {
+                        // fingerprints are public and are what redacted inventories are joined by
+                        "sha256" | "thumbprint" => value,
+                        _ => self.redact_names(value),
+                    }
+                };
+                (column, value)
+            })
+            .collect()
+    }
+
+    /// Redacts a certificate record, or a list of them
+    pub fn redact(&self, value: Value) -> Value {
+        let span = value.span();
+        match value {
+            Value::List { vals, .. } => {
+                Value::list(vals.into_iter().map(|val| self.redact(val)).collect(), span)
+            }
+            Value::Record { val, .. } => {
+                let record = val.into_owned();
+                // user principal names are account names, not hostnames
+                let upn = record
+                    .get("identity_type")
+                    .is_some_and(|kind| kind.as_str() == Ok("upn"));
+                let mut record = self.redact_record(record, upn);
+                // other names, like UPNs, are always hashed
+                if let Some(Value::List { vals, .. }) = record.get_mut("san_other") {
+                    for other in vals.iter_mut() {
+                        let othername = other
+                            .get_data_by_key("type")
+                            .is_some_and(|kind| kind.as_str() == Ok("othername"));
+                        if let (true, Value::Record { val, .. }) = (othername, other) {
+                            if let Some(value) = val.to_mut().get_mut("value") {
+                                *value = self.hash_all(value.clone());
+                            }
+                        }
+                    }
+                }
+                Value::record(record, span)
+            }
+            value => value,
+        }
+    }
+}
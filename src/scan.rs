@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use nu_protocol::Value;
+
+use crate::core::{config::Binding, filter::TargetFilter};
+
+/// Recursively lists the files below a path, in a stable order
+pub fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        entries.sort();
+        for entry in entries {
+            collect_files(&entry, files)?;
+        }
+    } else {
+        std::fs::metadata(path)?;
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Expands the haproxy `crt` directories into their files, and finds the keys next to them
+pub fn expand_binding(binding: Binding) -> Vec<Binding> {
+    if binding.format != "haproxy" {
+        return vec![binding];
+    }
+    let certs = match std::fs::read_dir(&binding.cert) {
+        Ok(entries) => {
+            let mut files = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && !is_extra_file(path))
+                .collect::<Vec<PathBuf>>();
+            files.sort();
+            files
+        }
+        Err(_) => vec![binding.cert.clone()],
+    };
+    // the key is either in the same file or next to it with a .key suffix
+    certs
+        .into_iter()
+        .map(|cert| {
+            let key = PathBuf::from(format!("{}.key", cert.display()));
+            Binding {
+                format: binding.format,
+                config: binding.config.clone(),
+                line: binding.line,
+                server: binding.server.clone(),
+                key: key.is_file().then_some(key),
+                cert,
+            }
+        })
+        .collect()
+}
+
+/// Files haproxy loads next to a certificate of a `crt` directory, and skips as certificates
+fn is_extra_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        ["key", "ocsp", "issuer", "sctl"].contains(&&*extension.to_string_lossy())
+    })
+}
+
+/// Reads the allowlist and denylist of the plugin config, like
+/// $env.config.plugins.cer.filter = {deny: ['*.internal' '/etc/ssl/private/*']},
+/// and adds the patterns of a `--filter` record, like `--filter (open filter.nuon)`
+pub fn get_target_filter(config: Option<&Value>, flag: Option<&Value>) -> TargetFilter {
+    let mut filter = TargetFilter::default();
+    for source in [
+        config.and_then(|config| config.get_data_by_key("filter")),
+        flag.cloned(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let patterns = |column: &str| {
+            source
+                .get_data_by_key(column)
+                .and_then(|patterns| patterns.into_list().ok())
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|pattern| pattern.into_string().ok())
+        };
+        filter.allow.extend(patterns("allow"));
+        filter.deny.extend(patterns("deny"));
+    }
+    filter
+}
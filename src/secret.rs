@@ -0,0 +1,32 @@
+//! Passwords, zeroed when they are dropped. Private keys are held in a core::key::KeyPem.
+//!
+//! Only the copies the plugin owns are wiped. Nushell keeps its own copy of every flag value and
+//! pipeline input, the directory client copies the bind password into its requests, and schannel
+//! copies the pfx password and the imported keys into the memory of the Windows CryptoAPI.
+
+use std::{fmt, ops::Deref};
+
+use zeroize::Zeroizing;
+
+/// A password that is wiped when dropped and never shown in debug output
+pub struct Secret(Zeroizing<String>);
+
+impl Secret {
+    pub fn new(secret: String) -> Self {
+        Secret(Zeroizing::new(secret))
+    }
+}
+
+impl Deref for Secret {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
@@ -0,0 +1,25 @@
+//! Tests over generated certificates and a corpus of fixtures with golden output
+
+mod catalog;
+mod coverage;
+mod detect;
+mod dn;
+mod expiry;
+mod filter;
+mod generate;
+mod golden;
+mod host;
+mod ingest;
+mod key;
+mod limits;
+mod lint;
+mod merge;
+#[cfg(all(windows, feature = "pkcs12"))]
+mod pfx;
+mod policy;
+mod preset;
+mod provenance;
+mod redact;
+mod roundtrip;
+mod scan;
+mod spiffe;
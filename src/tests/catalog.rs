@@ -0,0 +1,67 @@
+use nu_protocol::{record, LabeledError, Span, Value};
+
+use crate::catalog::Catalog;
+
+fn config() -> Value {
+    let span = Span::test_data();
+    Value::record(
+        record! {
+            "locale" => Value::string("de", span),
+            "catalogs" => Value::record(record! {
+                "de" => Value::record(record! {
+                    "{0} without digitalSignature" => Value::string("{0} ohne digitalSignature", span),
+                    "check that the path exists and is readable" => Value::string("Pfad prüfen", span),
+                }, span),
+            }, span),
+        },
+        span,
+    )
+}
+
+#[test]
+fn templates_are_translated_and_filled() {
+    let catalog = Catalog::from_config(Some(&config()));
+    assert_eq!(
+        catalog.text("{0} without digitalSignature", &["serverAuth"]),
+        "serverAuth ohne digitalSignature"
+    );
+    // untranslated templates fall back to English
+    assert_eq!(
+        catalog.text("{0} keys are not allowed by the profile", &["dsa"]),
+        "dsa keys are not allowed by the profile"
+    );
+}
+
+#[test]
+fn placeholders_in_arguments_are_not_filled() {
+    let catalog = Catalog::from_config(None);
+    assert_eq!(
+        catalog.text(
+            "{0} key of {1} bits is shorter than the {2} bits of the profile",
+            &["{1}", "1024", "{0}"]
+        ),
+        "{1} key of 1024 bits is shorter than the {0} bits of the profile"
+    );
+    assert_eq!(catalog.text("{x} and {3} stay", &["a"]), "{x} and {3} stay");
+}
+
+#[test]
+fn help_lines_are_translated_one_by_one() {
+    let catalog = Catalog::from_config(Some(&config()));
+    let error = LabeledError::new("cannot read file")
+        .with_help("No such file or directory\ncheck that the path exists and is readable");
+    let help = catalog.localize(error).help;
+    assert_eq!(
+        help.as_deref(),
+        Some("No such file or directory\nPfad prüfen")
+    );
+}
+
+#[test]
+fn no_locale_keeps_english() {
+    let catalog = Catalog::from_config(None);
+    assert_eq!(
+        catalog.text("{0} without digitalSignature", &["clientAuth"]),
+        "clientAuth without digitalSignature"
+    );
+}
@@ -0,0 +1,67 @@
+use nu_protocol::{Span, Value};
+
+use crate::{
+    certificate::{get_pem_values, RecordOptions, SAN_PREVIEW},
+    coverage::get_coverage,
+    error::CerError,
+    tests::generate::{generate_certificate, to_pem, Random},
+};
+
+#[test]
+fn hosts_past_the_san_preview_are_not_reported_uncovered() {
+    let span = Span::test_data();
+    let (der, expected) = (0..)
+        .map(|seed| generate_certificate(&mut Random::new(seed)))
+        .find(|(_der, expected)| expected.sans.len() > SAN_PREVIEW)
+        .expect("a certificate with many SANs");
+    let pem = to_pem("CERTIFICATE", &der);
+    let host = expected.sans.last().expect("a SAN").clone();
+
+    let truncated = get_pem_values(&pem, &RecordOptions::default(), span).expect("records");
+    assert!(matches!(
+        get_coverage(&truncated, vec![host.clone()], span),
+        Err(CerError::TruncatedSans {
+            shown: SAN_PREVIEW,
+            ..
+        })
+    ));
+
+    let options = RecordOptions {
+        full_sans: true,
+        ..Default::default()
+    };
+    let full = get_pem_values(&pem, &options, span).expect("records");
+    let coverage = get_coverage(&full, vec![host], span).expect("coverage");
+    assert_eq!(
+        coverage.get_data_by_key("uncovered"),
+        Some(Value::test_list(vec![]))
+    );
+    assert_eq!(
+        coverage
+            .get_data_by_key("unused")
+            .and_then(|unused| unused.into_list().ok())
+            .map(|unused| unused.len()),
+        Some(0)
+    );
+}
+
+#[test]
+fn san_types_do_not_count_as_truncated() {
+    let span = Span::test_data();
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tests/fixtures/ip-sans.pem"
+    );
+    let pem = std::fs::read_to_string(path).expect("fixture");
+    let options = RecordOptions {
+        full_sans: true,
+        san_types: Some(vec!["dns".into()]),
+        ..Default::default()
+    };
+    let records = get_pem_values(&pem, &options, span).expect("records");
+    let coverage = get_coverage(&records, vec!["ip.example".into()], span).expect("coverage");
+    assert_eq!(
+        coverage.get_data_by_key("uncovered"),
+        Some(Value::test_list(vec![]))
+    );
+}
@@ -0,0 +1,55 @@
+use data_encoding::BASE64;
+
+use crate::{
+    core::detect::detect,
+    tests::generate::{generate_certificate, to_pem, Random},
+};
+
+const PFX: &[u8] = include_bytes!("fixtures/with-key.pfx");
+
+#[test]
+fn formats_are_detected() {
+    let (der, _expected) = generate_certificate(&mut Random::new(7));
+    let pem = to_pem("CERTIFICATE", &der);
+    let cases = [
+        (pem.as_bytes().to_vec(), true, "pem"),
+        (
+            BASE64.encode(&der).into_bytes(),
+            true,
+            "base64-der-certificate",
+        ),
+        (der.clone(), false, "der-certificate"),
+        (PFX.to_vec(), false, "pfx"),
+        (vec![0xfe, 0xed, 0xfe, 0xed, 0, 0, 0, 2], false, "jks"),
+        (vec![0x30, 0x03, 0x02, 0x01, 0x05], false, "unknown"),
+    ];
+    for (data, text, format) in cases {
+        assert_eq!(detect(&data, text).format, format);
+    }
+    assert_eq!(detect(pem.as_bytes(), true).labels, ["CERTIFICATE"]);
+}
+
+#[test]
+fn base64_pfx_text_suggests_decoding() {
+    let detection = detect(BASE64.encode(PFX).as_bytes(), true);
+    assert_eq!(detection.format, "base64-pfx");
+    assert!(detection
+        .reasons
+        .iter()
+        .any(|reason| reason.contains("decode base64")));
+}
+
+#[test]
+fn prose_is_not_taken_for_base64() {
+    for text in [
+        "hello world",
+        "helloworld",
+        "a line of plain text\nand another one",
+    ] {
+        let detection = detect(text.as_bytes(), true);
+        assert_eq!(detection.format, "unknown");
+        assert_eq!(detection.reasons, ["text without PEM armor"], "{text}");
+    }
+    let detection = detect(b"QUJDRB==QUJDRB==", true);
+    assert!(detection.reasons[0].starts_with("text looks like base64 but does not decode"));
+}
@@ -0,0 +1,85 @@
+use x509_parser::der_parser::asn1_rs::Tag;
+
+use crate::core::dn::{decode_wide_string, format_dn, normalize_dn, parse_dn, Dn, DnAttribute};
+
+fn dn(rdns: &[&[(&str, &str)]]) -> Dn {
+    rdns.iter()
+        .map(|rdn| {
+            rdn.iter()
+                .map(|(name, value)| DnAttribute {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn hex_escapes_are_decoded_per_sequence() {
+    let cases = [
+        (r"CN=\C3\A9", "é"),
+        (r"CN=证\C3\A9", "证é"),
+        (r"CN=ü\C3\A9", "üé"),
+        ("CN=Ã©", "Ã©"),
+        (r"CN=a\2Cb\,c", "a,b,c"),
+    ];
+    for (text, value) in cases {
+        assert_eq!(
+            parse_dn(text).expect(text),
+            dn(&[&[("CN", value)]]),
+            "{text}"
+        );
+    }
+    assert!(parse_dn(r"CN=\C3").is_err());
+    assert!(parse_dn(r"CN=a\").is_err());
+}
+
+#[test]
+fn quoted_values_and_multi_valued_rdns_are_parsed() {
+    assert_eq!(
+        parse_dn(r#"CN="Doe, John";O=Example"#).expect("quoted"),
+        dn(&[&[("CN", "Doe, John")], &[("O", "Example")]])
+    );
+    assert_eq!(
+        parse_dn("CN=a+UID=1,O=b").expect("multi-valued"),
+        dn(&[&[("CN", "a"), ("UID", "1")], &[("O", "b")]])
+    );
+    assert!(parse_dn(r#"CN="open"#).is_err());
+    assert!(parse_dn("CN=a,=b").is_err());
+}
+
+#[test]
+fn normalized_names_compare_equal() {
+    let parse = |text| normalize_dn(&parse_dn(text).expect(text));
+    assert_eq!(
+        parse("CN=Example  Host+emailAddress=A@B.example, o=Org"),
+        parse("E=a@b.example+commonName=example host,2.5.4.10=ORG")
+    );
+    assert_ne!(parse("CN=a,O=b"), parse("O=b,CN=a"));
+}
+
+#[test]
+fn formatted_names_parse_back() {
+    let names = dn(&[&[("CN", "Doe, John"), ("UID", "a+b")], &[("O", " #lead")]]);
+    let text = format_dn(&names);
+    assert_eq!(text, r"CN=Doe\, John+UID=a\+b,O=\ #lead");
+    let parsed = parse_dn(&text).expect("formatted name");
+    assert_eq!(parsed[0], names[0]);
+}
+
+#[test]
+fn wide_strings_are_decoded() {
+    let bmp = [0x8a, 0x3c, 0x66, 0x0e, 0x00, 0x21];
+    assert_eq!(
+        decode_wide_string(Tag::BmpString, &bmp),
+        Some("証明!".into())
+    );
+    let universal = [0x00, 0x01, 0xf5, 0x12, 0x00, 0x00, 0x00, 0x41];
+    assert_eq!(
+        decode_wide_string(Tag::UniversalString, &universal),
+        Some("🔒A".into())
+    );
+    assert_eq!(decode_wide_string(Tag::BmpString, &bmp[..5]), None);
+    assert_eq!(decode_wide_string(Tag::Utf8String, b"text"), None);
+}
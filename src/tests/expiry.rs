@@ -0,0 +1,60 @@
+use chrono::{DateTime, Duration};
+use nu_protocol::{Span, Value};
+
+use crate::expiry::{
+    get_bucket, get_bucket_index, get_default_buckets, get_duration_name, parse_duration,
+};
+
+const DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[test]
+fn durations_parse_and_name_in_their_largest_unit() {
+    assert_eq!(parse_duration("90sec"), Some(90 * 1_000_000_000));
+    assert_eq!(parse_duration("2wk"), Some(14 * DAY));
+    assert_eq!(parse_duration("1yr"), Some(365 * DAY));
+    for invalid in ["day", "1", "1month", "-1day", "99999999999yr"] {
+        assert_eq!(parse_duration(invalid), None, "{invalid}");
+    }
+    assert_eq!(get_duration_name(7 * DAY), "1wk");
+    assert_eq!(get_duration_name(30 * DAY), "30day");
+    assert_eq!(get_duration_name(90 * 60 * 1_000_000_000), "90min");
+    assert_eq!(get_duration_name(1_500), "1500ns");
+}
+
+#[test]
+fn default_buckets_are_named_like_explicit_ones() {
+    let span = Span::test_data();
+    let explicit = [
+        Value::duration(7 * DAY, span),
+        Value::duration(30 * DAY, span),
+        Value::duration(90 * DAY, span),
+        Value::string("1yr", span),
+    ]
+    .iter()
+    .map(|value| get_bucket(value).expect("bucket"))
+    .collect::<Vec<_>>();
+    assert_eq!(get_default_buckets(span), explicit);
+    assert_eq!(explicit[0].0, "1wk");
+    assert_eq!(get_bucket(&Value::duration(0, span)), None);
+}
+
+#[test]
+fn expirations_fall_in_the_first_bucket_they_fit() {
+    let buckets = get_default_buckets(Span::test_data());
+    let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").expect("date");
+    let cases = [
+        (Duration::seconds(-1), 0),
+        (Duration::zero(), 1),
+        (Duration::days(7), 1),
+        (Duration::days(8), 2),
+        (Duration::days(365), 4),
+        (Duration::days(366), 5),
+    ];
+    for (remaining, index) in cases {
+        assert_eq!(
+            get_bucket_index(now + remaining, now, &buckets),
+            index,
+            "{remaining}"
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use crate::core::filter::{glob_matches, TargetFilter};
+
+#[test]
+fn globs_match_hostnames_and_paths() {
+    assert!(glob_matches("*.example.com", "www.Example.com"));
+    assert!(!glob_matches("*.example.com", "example.com"));
+    assert!(glob_matches("db-??.internal", "db-01.internal"));
+    assert!(glob_matches(
+        "/etc/ssl/*",
+        "\\etc\\ssl\\private\\server.pem"
+    ));
+    assert!(glob_matches("*a*b", "xaxxab"));
+    assert!(!glob_matches("*a*b", "xaxxa"));
+}
+
+#[test]
+fn denylist_wins_over_allowlist() {
+    let filter = TargetFilter {
+        allow: vec!["*.example.com".into()],
+        deny: vec!["*/staging/*".into()],
+    };
+    assert!(filter.allows(&["www.example.com", "/etc/nginx/site.conf"]));
+    assert!(!filter.allows(&["www.example.com", "/etc/nginx/staging/site.conf"]));
+    assert!(!filter.allows(&["www.example.org"]));
+    assert!(TargetFilter::default().allows(&["anything"]));
+}
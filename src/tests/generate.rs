@@ -0,0 +1,315 @@
+use data_encoding::{BASE64, HEXLOWER};
+
+/// Deterministic xorshift generator, so a failing case can be reproduced from its seed
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// What a generated certificate is expected to show
+pub struct Expected {
+    pub cns: Vec<String>,
+    /// the DNS names and IP addresses, what `san` shows without --san-types
+    pub sans: Vec<String>,
+    /// every SAN in order, with its kind as named by --san-types
+    pub names: Vec<(&'static str, String)>,
+    pub rdns: usize,
+    pub key_algorithm: &'static str,
+    pub key_size: usize,
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut der = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        der.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let bytes = &bytes[bytes.iter().position(|byte| *byte != 0).unwrap_or(7)..];
+        der.push(0x80 | bytes.len() as u8);
+        der.extend_from_slice(bytes);
+    }
+    der.extend_from_slice(content);
+    der
+}
+
+fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(0x30, &items.concat())
+}
+
+fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut content = vec![(arcs[0] * 40 + arcs[1]) as u8];
+    for arc in &arcs[2..] {
+        let mut base128 = vec![(*arc & 0x7f) as u8];
+        let mut rest = *arc >> 7;
+        while rest > 0 {
+            base128.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        base128.reverse();
+        content.extend(base128);
+    }
+    tlv(0x06, &content)
+}
+
+const CN: [u64; 4] = [2, 5, 4, 3];
+const NAME_ATTRIBUTES: [[u64; 4]; 6] = [
+    [2, 5, 4, 6],
+    [2, 5, 4, 7],
+    [2, 5, 4, 8],
+    [2, 5, 4, 10],
+    [2, 5, 4, 11],
+    [2, 5, 4, 5],
+];
+const ODD_TEXT: [&str; 8] = [
+    "plain",
+    "comma, inside",
+    "plus+sign",
+    "\"quoted\"",
+    " leading space",
+    "#hash",
+    "üñíçødé 証明書",
+    "back\\slash;semi=equals<>",
+];
+
+fn text(random: &mut Random) -> String {
+    let mut text = random.pick(&ODD_TEXT).to_string();
+    text.push_str(&random.below(1000).to_string());
+    text
+}
+
+fn name(random: &mut Random, cns: &mut Vec<String>) -> (Vec<u8>, usize) {
+    let mut rdns = Vec::new();
+    for _ in 0..random.below(4) {
+        let attributes = (0..1 + random.below(2))
+            .map(|_| {
+                let value = text(random);
+                let attribute = *random.pick(&NAME_ATTRIBUTES);
+                sequence(&[oid(&attribute), tlv(0x0c, value.as_bytes())])
+            })
+            .collect::<Vec<Vec<u8>>>();
+        rdns.push(tlv(0x31, &attributes.concat()));
+    }
+    for _ in 0..random.below(3) {
+        let cn = text(random);
+        rdns.push(tlv(0x31, &sequence(&[oid(&CN), tlv(0x0c, cn.as_bytes())])));
+        cns.push(cn);
+    }
+    (sequence(&rdns), rdns.len())
+}
+
+fn general_names(
+    random: &mut Random,
+    mixed: bool,
+    names: &mut Vec<(&'static str, String)>,
+) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    for index in 0..random.below(300) {
+        let kinds = if mixed { 6 } else { 3 };
+        match random.below(kinds) {
+            0 => {
+                let ip = random.bytes(4);
+                names.push(("ip", format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3])));
+                encoded.push(tlv(0x87, &ip));
+            }
+            1 => {
+                let ip = random.bytes(16);
+                let ip = std::net::Ipv6Addr::from(<[u8; 16]>::try_from(ip).unwrap_or_default());
+                names.push(("ip", ip.to_string()));
+                encoded.push(tlv(0x87, &ip.octets()));
+            }
+            3 => {
+                let email = format!("user-{}@mail-{}.example", index, random.below(1000));
+                encoded.push(tlv(0x81, email.as_bytes()));
+                names.push(("email", email));
+            }
+            4 => {
+                let uri = format!("spiffe://example.org/workload-{}", index);
+                encoded.push(tlv(0x86, uri.as_bytes()));
+                names.push(("uri", uri));
+            }
+            5 => {
+                // a Microsoft user principal name, otherName [0] EXPLICIT UTF8String
+                let upn = format!("user-{}@corp.example", index);
+                let value = tlv(0xa0, &tlv(0x0c, upn.as_bytes()));
+                let upn_oid = [1, 3, 6, 1, 4, 1, 311, 20, 2, 3];
+                encoded.push(tlv(0xa0, &[oid(&upn_oid), value.clone()].concat()));
+                names.push((
+                    "othername",
+                    format!("1.3.6.1.4.1.311.20.2.3:{}", HEXLOWER.encode(&value)),
+                ));
+            }
+            _ => {
+                let dns = format!("host-{}.{}.example", index, random.below(1_000_000));
+                encoded.push(tlv(0x82, dns.as_bytes()));
+                names.push(("dns", dns));
+            }
+        }
+    }
+    sequence(&encoded)
+}
+
+// P-256, P-384, Ed25519 or a 2048 bit RSA key, with random key material
+fn public_key(random: &mut Random) -> (Vec<u8>, &'static str, usize) {
+    let ec = |random: &mut Random, curve: &[u64], len: usize| {
+        let mut point = vec![0x00, 0x04];
+        point.extend(random.bytes(len));
+        sequence(&[
+            sequence(&[oid(&[1, 2, 840, 10045, 2, 1]), oid(curve)]),
+            tlv(0x03, &point),
+        ])
+    };
+    match random.below(4) {
+        0 => (ec(random, &[1, 2, 840, 10045, 3, 1, 7], 64), "ec-p256", 256),
+        1 => (ec(random, &[1, 3, 132, 0, 34], 96), "ec-p384", 384),
+        2 => {
+            let mut key = vec![0x00];
+            key.extend(random.bytes(32));
+            let spki = sequence(&[sequence(&[oid(&[1, 3, 101, 112])]), tlv(0x03, &key)]);
+            (spki, "ed25519", 256)
+        }
+        _ => {
+            let mut modulus = vec![0x00];
+            modulus.extend(random.bytes(256));
+            modulus[1] |= 0x80;
+            let rsa = sequence(&[tlv(0x02, &modulus), tlv(0x02, &[0x01, 0x00, 0x01])]);
+            let mut key = vec![0x00];
+            key.extend(rsa);
+            let spki = sequence(&[
+                sequence(&[oid(&[1, 2, 840, 113549, 1, 1, 1]), tlv(0x05, &[])]),
+                tlv(0x03, &key),
+            ]);
+            (spki, "rsa", 2048)
+        }
+    }
+}
+
+fn extension(arcs: &[u64], critical: bool, value: &[u8]) -> Vec<u8> {
+    let mut items = vec![oid(arcs)];
+    if critical {
+        items.push(tlv(0x01, &[0xff]));
+    }
+    items.push(tlv(0x04, value));
+    sequence(&items)
+}
+
+fn time(random: &mut Random) -> Vec<u8> {
+    let year = 1970 + random.below(120);
+    let time = format!(
+        "{:02}{:02}{:02}{:02}{:02}Z",
+        1 + random.below(12),
+        1 + random.below(28),
+        random.below(24),
+        random.below(60),
+        random.below(60)
+    );
+    // RFC 5280 section 4.1.2.5: UTCTime through 2049, GeneralizedTime from 2050
+    if year < 2050 {
+        tlv(0x17, format!("{:02}{}", year % 100, time).as_bytes())
+    } else {
+        tlv(0x18, format!("{}{}", year, time).as_bytes())
+    }
+}
+
+/// A DER certificate with a random subject, issuer, key, DNS and IP SANs and private extensions.
+/// Its signature is random bytes, parsing does not verify it.
+pub fn generate_certificate(random: &mut Random) -> (Vec<u8>, Expected) {
+    generate(random, false)
+}
+
+/// Like generate_certificate, with email, URI and otherName SANs among the others,
+/// which are only read with --san-types
+pub fn generate_mixed_certificate(random: &mut Random) -> (Vec<u8>, Expected) {
+    generate(random, true)
+}
+
+fn generate(random: &mut Random, mixed: bool) -> (Vec<u8>, Expected) {
+    let mut cns = Vec::new();
+    let mut names = Vec::new();
+    let (subject, rdns) = name(random, &mut cns);
+    let (issuer, _issuer_rdns) = name(random, &mut Vec::new());
+    let ecdsa_sha256 = sequence(&[oid(&[1, 2, 840, 10045, 4, 3, 2])]);
+    let (spki, key_algorithm, key_size) = public_key(random);
+    let mut extensions = Vec::new();
+    if random.below(4) > 0 {
+        extensions.push(extension(
+            &[2, 5, 29, 17],
+            random.below(2) == 0,
+            &general_names(random, mixed, &mut names),
+        ));
+    }
+    for index in 0..random.below(4) {
+        let len = random.below(2000);
+        let value = tlv(0x04, &random.bytes(len));
+        extensions.push(extension(
+            &[1, 3, 6, 1, 4, 1, 99999, index as u64],
+            false,
+            &value,
+        ));
+    }
+    let len = 1 + random.below(20);
+    let mut serial = random.bytes(len);
+    serial[0] &= 0x7f;
+    let mut tbs = vec![
+        tlv(0xa0, &tlv(0x02, &[2])),
+        tlv(0x02, &serial),
+        ecdsa_sha256.clone(),
+        issuer,
+        sequence(&[time(random), time(random)]),
+        subject,
+        spki,
+    ];
+    if !extensions.is_empty() {
+        tbs.push(tlv(0xa3, &sequence(&extensions)));
+    }
+    let mut signature = vec![0x00];
+    signature.extend(random.bytes(72));
+    let der = sequence(&[sequence(&tbs), ecdsa_sha256, tlv(0x03, &signature)]);
+    let sans = names
+        .iter()
+        .filter(|(kind, _name)| matches!(*kind, "dns" | "ip"))
+        .map(|(_kind, name)| name.clone())
+        .collect();
+    let expected = Expected {
+        cns,
+        sans,
+        names,
+        rdns,
+        key_algorithm,
+        key_size,
+    };
+    (der, expected)
+}
+
+pub fn to_pem(label: &str, der: &[u8]) -> String {
+    let base64 = BASE64.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in base64.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
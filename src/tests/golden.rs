@@ -0,0 +1,89 @@
+use std::{fs, path::Path};
+
+use nu_protocol::{Span, Value};
+
+use crate::{
+    certificate::{get_pem_values, RecordOptions},
+    digest::{canonical_json, json_safe},
+};
+
+// regenerate the golden files with CER_UPDATE_GOLDEN=1 cargo test, and review the diff
+fn render(pem: &String) -> String {
+    let options = RecordOptions {
+        rdns: true,
+        ..Default::default()
+    };
+    let span = Span::test_data();
+    let value = match get_pem_values(pem, &options, span) {
+        Ok(values) => Value::list(values, span),
+        Err(err) => Value::record(
+            [("error".to_string(), Value::string(err.details(), span))]
+                .into_iter()
+                .collect(),
+            span,
+        ),
+    };
+    let json = serde_json::from_str::<serde_json::Value>(&canonical_json(&value))
+        .expect("canonical json parses");
+    let mut rendered = serde_json::to_string_pretty(&json).expect("json renders");
+    rendered.push('\n');
+    rendered
+}
+
+#[test]
+fn fixtures_match_golden_files() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures");
+    let update = std::env::var_os("CER_UPDATE_GOLDEN").is_some();
+    let mut pems = fs::read_dir(&fixtures)
+        .expect("fixture directory")
+        .map(|entry| entry.expect("fixture entry").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "pem"))
+        .collect::<Vec<_>>();
+    pems.sort();
+    assert!(!pems.is_empty());
+    for pem in pems {
+        let rendered = render(&fs::read_to_string(&pem).expect("fixture"));
+        let golden = pem.with_extension("json");
+        if update {
+            fs::write(&golden, &rendered).expect("golden file written");
+        } else {
+            let expected = fs::read_to_string(&golden).unwrap_or_default();
+            assert_eq!(rendered, expected, "{} changed", pem.display());
+        }
+    }
+}
+
+// json_safe must not change what the golden files hold, only how nushell carries it
+#[test]
+fn json_safe_values_keep_their_canonical_json() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures");
+    let span = Span::test_data();
+    for entry in fs::read_dir(&fixtures).expect("fixture directory") {
+        let pem = entry.expect("fixture entry").path();
+        if pem.extension().is_none_or(|extension| extension != "pem") {
+            continue;
+        }
+        let pem = fs::read_to_string(&pem).expect("fixture");
+        let Ok(values) = get_pem_values(&pem, &RecordOptions::default(), span) else {
+            continue;
+        };
+        let value = Value::list(values, span);
+        let safe = json_safe(value.clone());
+        assert_eq!(canonical_json(&safe), canonical_json(&value));
+        for record in safe.as_list().expect("list") {
+            let record = record.as_record().expect("record");
+            assert!(record.columns().is_sorted());
+            assert!(record
+                .values()
+                .all(|value| !matches!(value, Value::Date { .. })));
+        }
+    }
+}
+
+#[test]
+fn date_strings_are_kept_byte_for_byte() {
+    let span = Span::test_data();
+    let value = Value::string("2024-01-01T05:00:00+05:00", span);
+    assert_eq!(canonical_json(&value), r#""2024-01-01T05:00:00+05:00""#);
+    assert_eq!(json_safe(value.clone()), value);
+}
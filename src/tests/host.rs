@@ -0,0 +1,18 @@
+use crate::core::host::{name_covers, normalize_host};
+
+#[test]
+fn wildcards_cover_exactly_one_label() {
+    assert!(name_covers("www.example.com", "WWW.example.com."));
+    assert!(name_covers("*.example.com", "api.example.com"));
+    assert!(!name_covers("*.example.com", "example.com"));
+    assert!(!name_covers("*.example.com", "a.b.example.com"));
+    assert!(!name_covers("*.example.com", ".example.com"));
+    assert!(!name_covers("w*.example.com", "www.example.com"));
+    assert!(name_covers("10.0.0.1", "10.0.0.1"));
+}
+
+#[test]
+fn hosts_normalize_without_case_or_trailing_dot() {
+    assert_eq!(normalize_host("WWW.Example.com."), "www.example.com");
+    assert_eq!(normalize_host("*.example.com"), "*.example.com");
+}
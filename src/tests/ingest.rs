@@ -0,0 +1,145 @@
+use data_encoding::BASE64;
+use nu_protocol::{record, Span, Value};
+
+use crate::{certificate::RecordOptions, ingest::get_ingested_values};
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/src/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(path).expect("fixture")
+}
+
+fn options() -> RecordOptions {
+    RecordOptions {
+        san_types: Some(vec!["dns".into(), "ip".into()]),
+        ..Default::default()
+    }
+}
+
+fn column(value: &Value, column: &str) -> Value {
+    value.get_data_by_key(column).expect("column")
+}
+
+#[test]
+fn istio_secrets_are_read_with_their_source() {
+    let span = Span::test_data();
+    let chain = fixture("ev-chain.pem");
+    let secrets = record!(
+        "dynamicActiveSecrets" => Value::test_list(vec![
+            Value::test_record(record!(
+                "name" => Value::test_string("default"),
+                "secret" => Value::test_record(record!(
+                    "tlsCertificate" => Value::test_record(record!(
+                        "certificateChain" => Value::test_record(record!(
+                            "inlineBytes" => Value::test_string(BASE64.encode(chain.as_bytes())),
+                        )),
+                    )),
+                )),
+            )),
+            Value::test_record(record!(
+                "name" => Value::test_string("ROOTCA"),
+                "secret" => Value::test_record(record!(
+                    "validationContext" => Value::test_record(record!(
+                        "trustedCa" => Value::test_record(record!(
+                            "inlineString" => Value::test_string(fixture("ed25519.pem")),
+                        )),
+                    )),
+                )),
+            )),
+        ]),
+    );
+    let values = get_ingested_values(&secrets, &options(), span)
+        .expect("records")
+        .expect("istio shape");
+    let chain_count = chain.matches("BEGIN CERTIFICATE").count();
+    assert_eq!(values.len(), chain_count + 1);
+    assert_eq!(column(&values[0], "secret"), Value::test_string("default"));
+    assert_eq!(
+        column(&values[0], "part"),
+        Value::test_string("certificate_chain")
+    );
+    assert_eq!(column(&values[0], "state"), Value::test_string("active"));
+    let root = values.last().expect("root");
+    assert_eq!(column(root, "secret"), Value::test_string("ROOTCA"));
+    assert_eq!(column(root, "part"), Value::test_string("trusted_ca"));
+}
+
+#[test]
+fn vault_responses_are_read_by_part() {
+    let ca = fixture("ed25519.pem");
+    let response = record!(
+        "request_id" => Value::test_string("4f0c"),
+        "data" => Value::test_record(record!(
+            "certificate" => Value::test_string(fixture("ip-sans.pem")),
+            "issuing_ca" => Value::test_string(ca.clone()),
+            "ca_chain" => Value::test_list(vec![Value::test_string(ca)]),
+            "serial_number" => Value::test_string("1a:2b"),
+        )),
+    );
+    let values = get_ingested_values(&response, &options(), Span::test_data())
+        .expect("records")
+        .expect("vault shape");
+    let parts = values
+        .iter()
+        .map(|value| column(value, "part"))
+        .collect::<Vec<Value>>();
+    assert_eq!(
+        parts,
+        ["certificate", "issuing_ca", "ca_chain"].map(Value::test_string)
+    );
+    // without a private key in the response there is nothing to match
+    assert_eq!(column(&values[0], "key_match"), Value::test_nothing());
+}
+
+#[test]
+fn cloud_metadata_is_normalized() {
+    let span = Span::test_data();
+    let description = record!(
+        "Certificate" => Value::test_record(record!(
+            "CertificateArn" => Value::test_string("arn:aws:acm:eu-west-1:1:certificate/7f"),
+            "DomainName" => Value::test_string("www.example.com"),
+            "Type" => Value::test_string("AMAZON_ISSUED"),
+            "Status" => Value::test_string("ISSUED"),
+            "RenewalEligibility" => Value::test_string("ELIGIBLE"),
+            "InUseBy" => Value::test_list(vec![Value::test_string("arn:aws:elasticloadbalancing:lb")]),
+            "NotAfter" => Value::test_string("2027-01-31T23:59:59+00:00"),
+        )),
+    );
+    let acm = get_ingested_values(&description, &options(), span)
+        .expect("records")
+        .expect("acm shape");
+    assert_eq!(column(&acm[0], "provider"), Value::test_string("acm"));
+    assert_eq!(
+        column(&acm[0], "renewal_status"),
+        Value::test_string("ELIGIBLE")
+    );
+    assert!(matches!(column(&acm[0], "expiration"), Value::Date { .. }));
+
+    let gcp = record!(
+        "name" => Value::test_string("projects/p/locations/global/certificates/web"),
+        "pemCertificate" => Value::test_string(fixture("ip-sans.pem")),
+        "managed" => Value::test_record(record!(
+            "state" => Value::test_string("ACTIVE"),
+            "authorizationAttemptInfo" => Value::test_list(vec![Value::test_record(record!(
+                "domain" => Value::test_string("www.example.com"),
+                "state" => Value::test_string("AUTHORIZED"),
+            ))]),
+        )),
+    );
+    let gcp = get_ingested_values(&gcp, &options(), span)
+        .expect("records")
+        .expect("gcp shape");
+    assert_eq!(column(&gcp[0], "type"), Value::test_string("managed"));
+    assert_eq!(column(&gcp[0], "status"), Value::test_string("ACTIVE"));
+    assert_eq!(
+        column(&gcp[0], "renewal_status"),
+        Value::test_string("AUTHORIZED")
+    );
+    assert!(gcp[0].get_data_by_key("sha256").is_some());
+}
+
+#[test]
+fn unknown_records_are_not_read() {
+    let unknown = record!("name" => Value::test_string("default"));
+    let values = get_ingested_values(&unknown, &options(), Span::test_data()).expect("no error");
+    assert!(values.is_none());
+}
@@ -0,0 +1,13 @@
+use crate::core::key::get_strength_status;
+
+#[test]
+fn strength_status_changes_at_the_year_boundaries() {
+    assert_eq!(get_strength_status(112, 2030), ("acceptable", Some(2030)));
+    assert_eq!(get_strength_status(112, 2031), ("deprecated", Some(2030)));
+    assert_eq!(get_strength_status(112, 2035), ("deprecated", Some(2030)));
+    assert_eq!(get_strength_status(112, 2036), ("disallowed", Some(2030)));
+    assert_eq!(get_strength_status(128, 2035), ("acceptable", Some(2035)));
+    assert_eq!(get_strength_status(128, 2036), ("disallowed", Some(2035)));
+    assert_eq!(get_strength_status(80, 2024), ("disallowed", None));
+    assert_eq!(get_strength_status(0, 2024), ("unknown", None));
+}
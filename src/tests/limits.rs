@@ -0,0 +1,64 @@
+use nu_protocol::Span;
+
+use crate::{
+    certificate::{get_pem_values, get_residual, RecordOptions},
+    core::limits::Limits,
+    error::CerError,
+    tests::generate::{generate_certificate, to_pem, Random},
+};
+
+fn bundle(certificates: usize) -> String {
+    let mut random = Random::new(7);
+    (0..certificates)
+        .map(|_| to_pem("CERTIFICATE", &generate_certificate(&mut random).0))
+        .collect()
+}
+
+fn limited(limits: Limits) -> RecordOptions {
+    RecordOptions {
+        limits,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn certificates_over_the_limit_are_rejected() {
+    let options = limited(Limits {
+        max_certificates: 5,
+        ..Default::default()
+    });
+    let span = Span::test_data();
+    assert!(get_pem_values(&bundle(5), &options, span).is_ok());
+    assert!(matches!(
+        get_pem_values(&bundle(6), &options, span),
+        Err(CerError::Limit {
+            what: "certificates",
+            limit: 5
+        })
+    ));
+}
+
+#[test]
+fn output_over_the_budget_is_rejected() {
+    let options = limited(Limits {
+        max_output_size: 4096,
+        ..Default::default()
+    });
+    assert!(matches!(
+        get_pem_values(&bundle(50), &options, Span::test_data()),
+        Err(CerError::Limit {
+            what: "bytes of output",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn residuals_past_the_duration_range_saturate() {
+    // 9999-12-31T23:59:59Z, the notAfter of RFC 5280 section 4.1.2.5 for no expiration
+    let no_expiration = 253_402_300_799;
+    let now = 1_791_936_000;
+    assert_eq!(get_residual(no_expiration, now), i64::MAX);
+    assert_eq!(get_residual(now - 1, now), -1_000_000_000);
+    assert_eq!(get_residual(now + 86400, now), 86_400_000_000_000);
+}
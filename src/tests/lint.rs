@@ -0,0 +1,121 @@
+use nu_protocol::{Record, Span, Value};
+
+use crate::lint::{check_key_usage, check_profile, get_profile, Finding, Profile};
+
+fn strings(strings: &[&str]) -> Vec<String> {
+    strings.iter().map(|string| string.to_string()).collect()
+}
+
+fn templates(findings: &[Finding]) -> Vec<&'static str> {
+    findings.iter().map(|(template, _args)| *template).collect()
+}
+
+/// Key algorithm, key usage, extended key usage, CA flag and the expected findings
+type KeyUsageCase = (
+    &'static str,
+    &'static [&'static str],
+    &'static [&'static str],
+    bool,
+    &'static [&'static str],
+);
+
+#[test]
+fn key_usage_must_fit_the_key_and_purpose() {
+    let cases: [KeyUsageCase; 6] = [
+        (
+            "ec-p256",
+            &["digitalSignature", "keyEncipherment"],
+            &[],
+            false,
+            &["{0} is not possible with {1} keys"],
+        ),
+        (
+            "rsa",
+            &["digitalSignature", "keyAgreement"],
+            &[],
+            false,
+            &["{0} is not possible with {1} keys"],
+        ),
+        (
+            "ec-p256",
+            &["digitalSignature", "encipherOnly"],
+            &[],
+            false,
+            &["encipherOnly and decipherOnly require keyAgreement"],
+        ),
+        (
+            "rsa",
+            &["keyEncipherment"],
+            &["serverAuth"],
+            false,
+            &["{0} without digitalSignature"],
+        ),
+        (
+            "rsa",
+            &["digitalSignature", "cRLSign"],
+            &[],
+            true,
+            &["certificate authority without keyCertSign"],
+        ),
+        (
+            "ec-p256",
+            &["digitalSignature", "keyAgreement"],
+            &["serverAuth"],
+            false,
+            &[],
+        ),
+    ];
+    for (algorithm, key_usage, eku, ca, expected) in cases {
+        let findings = check_key_usage(algorithm, &strings(key_usage), &strings(eku), ca);
+        assert_eq!(templates(&findings), expected, "{algorithm} {key_usage:?}");
+    }
+}
+
+fn regime(config: Option<&Value>, name: &str) -> Vec<&'static str> {
+    let Some(Profile::Regime(profile)) = get_profile(config, name) else {
+        panic!("{name} is not a lint profile");
+    };
+    let mut findings = Vec::new();
+    check_profile(&profile, "ec-p256", 256, "sha256", &mut findings);
+    check_profile(&profile, "rsa", 2048, "sha384", &mut findings);
+    templates(&findings)
+}
+
+#[test]
+fn profiles_check_algorithms_sizes_and_hashes() {
+    assert_eq!(regime(None, "cabf"), Vec::<&str>::new());
+    assert_eq!(
+        regime(None, "cnsa2.0"),
+        [
+            "{0} keys are not allowed by the profile",
+            "{0} signatures are not allowed by the profile",
+            "{0} key of {1} bits is shorter than the {2} bits of the profile",
+        ]
+    );
+    assert!(get_profile(None, "unknown").is_none());
+    assert!(get_profile(None, "policy").is_none());
+}
+
+#[test]
+fn custom_profiles_skip_missing_settings() {
+    let span = Span::test_data();
+    let record = |columns: Vec<(&str, Value)>| {
+        Value::record(
+            columns
+                .into_iter()
+                .map(|(column, value)| (column.to_string(), value))
+                .collect::<Record>(),
+            span,
+        )
+    };
+    let hashes = Value::list(vec![Value::string("sha384", span)], span);
+    let config = record(vec![(
+        "lint_profiles",
+        record(vec![("internal", record(vec![("hashes", hashes)]))]),
+    )]);
+    assert_eq!(
+        regime(Some(&config), "internal"),
+        ["{0} signatures are not allowed by the profile"]
+    );
+    assert!(get_profile(Some(&config), "other").is_none());
+}
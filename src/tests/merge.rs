@@ -0,0 +1,72 @@
+use nu_protocol::{record, Value};
+
+use crate::merge::merge_tables;
+
+fn sources(row: &Value) -> Vec<String> {
+    row.get_data_by_key("sources")
+        .and_then(|sources| sources.into_list().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|source| source.into_string().ok())
+        .collect()
+}
+
+#[test]
+fn thumbprint_rows_merge_with_sha256_rows() {
+    let tables = record! {
+        "scan" => Value::test_list(vec![Value::test_record(record! {
+            "sha256" => Value::test_string("AA11"),
+            "cn" => Value::test_string("example.com"),
+        })]),
+        "store" => Value::test_list(vec![Value::test_record(record! {
+            "thumbprint" => Value::test_string("bb22"),
+            "store" => Value::test_string("My"),
+        })]),
+        "acm" => Value::test_list(vec![Value::test_record(record! {
+            "sha256" => Value::test_string("aa11"),
+            "thumbprint" => Value::test_string("BB22"),
+            "cn" => Value::test_string("www.example.com"),
+        })]),
+    };
+    let rows = merge_tables(&tables, nu_protocol::Span::test_data()).expect("merged");
+    assert_eq!(rows.len(), 1);
+    assert_eq!(sources(&rows[0]), ["scan", "store", "acm"]);
+    assert_eq!(
+        rows[0].get_data_by_key("store"),
+        Some(Value::test_string("My"))
+    );
+    let conflicts = rows[0]
+        .get_data_by_key("conflicts")
+        .and_then(|conflicts| conflicts.into_list().ok())
+        .unwrap_or_default();
+    assert_eq!(conflicts.len(), 1);
+}
+
+#[test]
+fn merged_output_merges_again_without_duplicate_columns() {
+    let span = nu_protocol::Span::test_data();
+    let tables = record! {
+        "a" => Value::test_list(vec![Value::test_record(record! {
+            "sha256" => Value::test_string("aa11"),
+        })]),
+        "b" => Value::test_list(vec![Value::test_record(record! {
+            "sha256" => Value::test_string("aa11"),
+        })]),
+    };
+    let merged = merge_tables(&tables, span).expect("merged");
+    let again = record! {
+        "merged" => Value::test_list(merged),
+        "c" => Value::test_list(vec![Value::test_record(record! {
+            "sha256" => Value::test_string("aa11"),
+        })]),
+    };
+    let rows = merge_tables(&again, span).expect("merged again");
+    let columns = rows[0]
+        .as_record()
+        .expect("record")
+        .columns()
+        .cloned()
+        .collect::<Vec<String>>();
+    assert_eq!(columns, ["sha256", "sources", "conflicts"]);
+    assert_eq!(sources(&rows[0]), ["merged", "c"]);
+}
@@ -0,0 +1,95 @@
+use std::{collections::BTreeSet, env, fs, path::PathBuf, thread};
+
+use nu_protocol::{Span, Value};
+
+use crate::{
+    certificate::RecordOptions,
+    pfx::{export_pfx, get_pfx_values},
+};
+
+const PFX: &[u8] = include_bytes!("fixtures/with-key.pfx");
+
+// the user key containers of CNG and of the legacy CryptoAPI providers
+fn key_files() -> BTreeSet<PathBuf> {
+    let Some(appdata) = env::var_os("APPDATA") else {
+        return BTreeSet::new();
+    };
+    let crypto = PathBuf::from(appdata).join("Microsoft").join("Crypto");
+    let mut files = BTreeSet::new();
+    let mut directories = vec![crypto.join("Keys"), crypto.join("RSA")];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else {
+                files.insert(path);
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn concurrent_imports_never_persist_keys() {
+    let before = key_files();
+    let imports = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                get_pfx_values(
+                    PFX,
+                    Some("fixture"),
+                    true,
+                    &RecordOptions::default(),
+                    Span::test_data(),
+                )
+                .expect("pfx imports")
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut sha256s = BTreeSet::new();
+    for import in imports {
+        let values = import.join().expect("import thread");
+        assert_eq!(values.len(), 1);
+        let key = values[0]
+            .get_data_by_key("private_key")
+            .expect("private_key");
+        assert_eq!(
+            key.get_data_by_key("algorithm"),
+            Some(Value::test_string("rsa"))
+        );
+        assert_eq!(key.get_data_by_key("size"), Some(Value::test_int(2048)));
+        sha256s.insert(
+            values[0]
+                .get_data_by_key("sha256")
+                .expect("sha256")
+                .into_string()
+                .expect("string"),
+        );
+    }
+    assert_eq!(sha256s.len(), 1);
+    assert_eq!(
+        key_files(),
+        before,
+        "a private key was written to a key container"
+    );
+}
+
+#[test]
+fn exported_keys_only_open_with_the_passphrase() {
+    let before = key_files();
+    let exported = export_pfx(PFX, Some("fixture"), "passphrase").expect("pfx exports");
+    let options = RecordOptions::default();
+    let span = Span::test_data();
+    assert!(get_pfx_values(&exported, Some("fixture"), false, &options, span).is_err());
+    let values =
+        get_pfx_values(&exported, Some("passphrase"), true, &options, span).expect("pfx imports");
+    assert!(values[0]
+        .get_data_by_key("private_key")
+        .is_some_and(|key| !key.is_nothing()));
+    assert_eq!(
+        key_files(),
+        before,
+        "a private key was written to a key container"
+    );
+}
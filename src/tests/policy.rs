@@ -0,0 +1,59 @@
+use nu_protocol::{record, Span, Value};
+
+use crate::policy::{Policy, PolicyFacts};
+
+fn get_policy() -> Policy {
+    Policy {
+        allowed_issuers: Some(vec!["*Example CA*".into()]),
+        min_key_sizes: vec![("rsa".into(), 3072)],
+        max_validity_days: Some(398),
+        required_ekus: vec!["serverAuth".into()],
+        banned_algorithms: vec!["sha1".into()],
+    }
+}
+
+#[test]
+fn records_are_checked_by_their_columns() {
+    let span = Span::test_data();
+    let security = Value::test_record(record! {
+        "cn" => Value::test_list(vec![Value::test_string("example.com")]),
+        "ca" => Value::test_list(vec![Value::test_string("Other CA")]),
+        "key_algorithm" => Value::test_string("rsa"),
+        "key_size" => Value::test_int(2048),
+        "signature_hash" => Value::test_string("sha1"),
+    });
+    let (violations, unchecked) = PolicyFacts::from_record(&security).check(&get_policy());
+    let templates = violations
+        .iter()
+        .map(|(template, _args)| *template)
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        templates,
+        [
+            "issuer {0} is not allowed by the policy",
+            "{0} key of {1} bits is shorter than the {2} bits of the policy",
+            "{0} is banned by the policy",
+        ]
+    );
+    assert_eq!(unchecked, ["max_validity_days", "required_ekus"]);
+
+    let lint = Value::record(
+        record! {
+            "key_algorithm" => Value::test_string("rsa"),
+            "key_size" => Value::test_int(4096),
+            "signature_hash" => Value::test_string("sha256"),
+            "key_usage" => Value::test_list(vec![Value::test_string("digitalSignature")]),
+            "eku" => Value::test_list(vec![Value::test_string("clientAuth")]),
+        },
+        span,
+    );
+    let (violations, unchecked) = PolicyFacts::from_record(&lint).check(&get_policy());
+    assert_eq!(
+        violations,
+        [(
+            "{0} is required by the policy",
+            vec!["serverAuth".to_string()]
+        )]
+    );
+    assert_eq!(unchecked, ["allowed_issuers", "max_validity_days"]);
+}
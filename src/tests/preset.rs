@@ -0,0 +1,40 @@
+use nu_protocol::{record, Value};
+
+use crate::preset::{get_preset, select_columns};
+
+#[test]
+fn configured_presets_override_the_built_in_ones() {
+    let config = Value::test_record(record!(
+        "presets" => Value::test_record(record!(
+            "inventory" => Value::test_list(vec![
+                Value::test_string("cn"),
+                Value::test_string("source"),
+            ]),
+        )),
+    ));
+    assert_eq!(
+        get_preset(Some(&config), "inventory"),
+        Some(vec!["cn".into(), "source".into()])
+    );
+    assert!(get_preset(Some(&config), "tls-debug").is_some_and(|columns| columns.len() == 7));
+    assert_eq!(get_preset(None, "compliance"), None);
+}
+
+#[test]
+fn columns_are_selected_in_preset_order() {
+    let certificate = Value::test_record(record!(
+        "cn" => Value::test_string("cer.com"),
+        "ca" => Value::test_string("Example CA"),
+        "sha256" => Value::test_string("3f1c"),
+    ));
+    let columns = vec!["sha256".into(), "cn".into(), "source".into()];
+    let selected = select_columns(Value::test_list(vec![certificate]), &columns);
+    assert_eq!(
+        selected,
+        Value::test_list(vec![Value::test_record(record!(
+            "sha256" => Value::test_string("3f1c"),
+            "cn" => Value::test_string("cer.com"),
+            "source" => Value::test_nothing(),
+        ))])
+    );
+}
@@ -0,0 +1,26 @@
+use nu_protocol::{record, Value};
+
+use crate::provenance::add_provenance;
+
+#[test]
+fn every_column_gets_its_origin() {
+    let certificate = Value::test_record(record!(
+        "secret" => Value::test_string("default"),
+        "cn" => Value::test_string("cer.com"),
+        "identity" => Value::test_string("cer.com"),
+        "sha256" => Value::test_string("3f1c"),
+    ));
+    let provenance = add_provenance(Value::test_list(vec![certificate]))
+        .into_list()
+        .expect("list")[0]
+        .get_data_by_key("provenance");
+    assert_eq!(
+        provenance,
+        Some(Value::test_record(record!(
+            "secret" => Value::test_string("input"),
+            "cn" => Value::test_string("extracted"),
+            "identity" => Value::test_string("heuristic"),
+            "sha256" => Value::test_string("computed"),
+        )))
+    );
+}
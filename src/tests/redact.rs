@@ -0,0 +1,123 @@
+use nu_protocol::{record, Value};
+
+use crate::redact::RedactPolicy;
+
+#[test]
+fn internal_names_are_hashed_and_public_ones_kept() {
+    let policy = RedactPolicy {
+        strip: vec!["ca".into()],
+        ..Default::default()
+    };
+    let certificate = Value::test_record(record!(
+        "subject" => Value::test_string("CN=db.corp, O=Example"),
+        "san" => Value::test_list(vec![
+            Value::test_string("www.example.com"),
+            Value::test_string("db.corp"),
+            Value::test_string("10.1.2.3"),
+            Value::test_string("8.8.8.8"),
+        ]),
+        "ca" => Value::test_list(vec![Value::test_string("Example CA")]),
+        "sha256" => Value::test_string("3f1c"),
+    ));
+    let redacted = policy.redact(certificate);
+
+    let column = |column: &str| redacted.get_data_by_key(column);
+    let sans = column("san").expect("san").into_list().expect("list");
+    assert_eq!(sans[0], Value::test_string("www.example.com"));
+    assert!(sans[1]
+        .as_str()
+        .is_ok_and(|san| san.starts_with("redacted:")));
+    assert!(sans[2]
+        .as_str()
+        .is_ok_and(|san| san.starts_with("redacted:")));
+    assert_eq!(sans[3], Value::test_string("8.8.8.8"));
+    let subject = column("subject")
+        .expect("subject")
+        .into_string()
+        .expect("string");
+    assert!(subject.starts_with("CN=redacted:") && subject.ends_with(", O=Example"));
+    assert!(column("ca").is_none());
+    assert_eq!(column("sha256"), Some(Value::test_string("3f1c")));
+}
+
+#[test]
+fn serial_numbers_are_hashed() {
+    let certificate = Value::test_record(record!(
+        "subject" => Value::test_string("serialNumber=1234567, O=Example, CN=www.example.com"),
+        "subject_rdns" => Value::test_list(vec![Value::test_list(vec![Value::test_record(record!(
+            "oid" => Value::test_string("2.5.4.5"),
+            "name" => Value::test_string("serialNumber"),
+            "value" => Value::test_string("1234567"),
+        ))])]),
+        "serial_number" => Value::test_string("3a:9f:01"),
+    ));
+    let redacted = RedactPolicy::default().redact(certificate);
+
+    let hashed = |value: Option<Value>| {
+        value
+            .and_then(|value| value.into_string().ok())
+            .is_some_and(|value| value.starts_with("redacted:"))
+    };
+    let subject = redacted
+        .get_data_by_key("subject")
+        .and_then(|subject| subject.into_string().ok())
+        .expect("subject");
+    assert!(subject.starts_with("serialNumber=redacted:"));
+    assert!(subject.ends_with(", O=Example, CN=www.example.com"));
+    let rdn = redacted
+        .get_data_by_key("subject_rdns")
+        .and_then(|rdns| rdns.into_list().ok())
+        .and_then(|rdns| {
+            rdns.into_iter()
+                .next()?
+                .into_list()
+                .ok()?
+                .into_iter()
+                .next()
+        })
+        .expect("rdn attribute");
+    assert!(hashed(rdn.get_data_by_key("value")));
+    assert_eq!(
+        rdn.get_data_by_key("name"),
+        Some(Value::test_string("serialNumber"))
+    );
+    assert!(hashed(redacted.get_data_by_key("serial_number")));
+}
+
+#[test]
+fn email_addresses_are_hashed_wherever_they_appear() {
+    let certificate = Value::test_record(record!(
+        "subject" => Value::test_string("CN=John Doe, emailAddress=john@example.com"),
+        "ca_subject" => Value::test_string("Email=pki@example.com, O=Example"),
+        "san" => Value::test_list(vec![
+            Value::test_string("www.example.com"),
+            Value::test_string("john@example.com"),
+        ]),
+        "san_other" => Value::test_list(vec![Value::test_record(record!(
+            "type" => Value::test_string("email"),
+            "value" => Value::test_string("john@example.com"),
+        ))]),
+        "subject_rdns" => Value::test_list(vec![Value::test_list(vec![Value::test_record(record!(
+            "oid" => Value::test_string("1.2.840.113549.1.9.1"),
+            "name" => Value::test_string("emailAddress"),
+            "value" => Value::test_string("john@example.com"),
+        ))])]),
+    ));
+    let redacted = RedactPolicy::default().redact(certificate);
+
+    let text = |value: Option<Value>| value.and_then(|value| value.into_string().ok());
+    let subject = text(redacted.get_data_by_key("subject")).expect("subject");
+    assert!(subject.starts_with("CN=John Doe, emailAddress=redacted:"));
+    let ca_subject = text(redacted.get_data_by_key("ca_subject")).expect("ca_subject");
+    assert!(ca_subject.starts_with("Email=redacted:") && ca_subject.ends_with(", O=Example"));
+    let sans = redacted
+        .get_data_by_key("san")
+        .and_then(|sans| sans.into_list().ok())
+        .expect("san");
+    assert_eq!(sans[0], Value::test_string("www.example.com"));
+    assert!(text(Some(sans[1].clone())).is_some_and(|san| san.starts_with("redacted:")));
+    for column in ["san_other", "subject_rdns"] {
+        let rendered = format!("{:?}", redacted.get_data_by_key(column));
+        assert!(!rendered.contains('@'), "{column} keeps an email address");
+    }
+}
@@ -0,0 +1,214 @@
+use nu_protocol::{Span, Value};
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::{
+    certificate::{get_pem_values, get_record, get_sha256, RecordOptions, SAN_PREVIEW},
+    tests::generate::{generate_certificate, generate_mixed_certificate, to_pem, Random},
+};
+
+const CASES: u64 = 300;
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_list().ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| value.as_str().ok().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn generated_certificates_round_trip() {
+    let span = Span::test_data();
+    let mut sans = 0;
+    for seed in 0..CASES {
+        let mut random = Random::new(seed);
+        let (der, expected) = generate_certificate(&mut random);
+        let options = RecordOptions {
+            rdns: seed % 2 == 0,
+            full_sans: seed % 3 == 0,
+            key_details: seed % 5 == 0,
+            ..Default::default()
+        };
+
+        let (_rem, cer) = X509Certificate::from_der(&der).expect("generated certificate parses");
+        let direct = get_record(&cer, &options, span).expect("record of the der");
+
+        let pem = to_pem("CERTIFICATE", &der);
+        let values = get_pem_values(&pem, &options, span).expect("record of the pem");
+        assert_eq!(values.len(), 1, "seed {}", seed);
+        let mut record = values[0].as_record().expect("a record").clone();
+        assert_eq!(
+            record.remove("sha256"),
+            Some(get_sha256(&der, span)),
+            "seed {}",
+            seed
+        );
+        assert!(record.remove("thumbprint").is_some(), "seed {}", seed);
+        assert_eq!(
+            Value::record(record.clone(), span),
+            Value::record(direct, span),
+            "seed {}",
+            seed
+        );
+
+        assert_eq!(strings(record.get("cn")), expected.cns, "seed {}", seed);
+        let shown = if options.full_sans {
+            expected.sans.len()
+        } else {
+            expected.sans.len().min(SAN_PREVIEW)
+        };
+        assert_eq!(
+            strings(record.get("san")),
+            expected.sans[..shown],
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            record
+                .get("san_count")
+                .and_then(|count| count.as_int().ok()),
+            Some(expected.sans.len() as i64),
+            "seed {}",
+            seed
+        );
+        sans += expected.sans.len();
+        if options.key_details {
+            assert_eq!(
+                record.get("key_algorithm"),
+                Some(&Value::string(expected.key_algorithm, span)),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                record.get("key_size"),
+                Some(&Value::int(expected.key_size as i64, span)),
+                "seed {}",
+                seed
+            );
+        }
+        if options.rdns {
+            let rdns = record
+                .get("subject_rdns")
+                .and_then(|rdns| rdns.as_list().ok());
+            assert_eq!(
+                rdns.map(<[Value]>::len),
+                Some(expected.rdns),
+                "seed {}",
+                seed
+            );
+        }
+    }
+    // guards against a generator that stopped producing the interesting cases
+    assert!(sans > CASES as usize);
+}
+
+#[test]
+fn mixed_sans_round_trip_with_san_types() {
+    let span = Span::test_data();
+    let kinds = ["dns", "ip", "email", "uri", "othername"];
+    let mut others = 0;
+    for seed in 0..CASES {
+        let mut random = Random::new(seed);
+        let (der, expected) = generate_mixed_certificate(&mut random);
+        // a random selection of kinds goes to san, the others to san_other
+        let san_types = kinds
+            .iter()
+            .filter(|_kind| random.below(2) == 0)
+            .map(|kind| kind.to_string())
+            .collect::<Vec<String>>();
+        let options = RecordOptions {
+            full_sans: seed % 3 == 0,
+            san_types: Some(san_types.clone()),
+            ..Default::default()
+        };
+        let values = get_pem_values(&to_pem("CERTIFICATE", &der), &options, span)
+            .expect("record of the pem");
+        let record = values[0].as_record().expect("a record");
+
+        let (sans, other): (Vec<_>, Vec<_>) = expected
+            .names
+            .iter()
+            .partition(|(kind, _name)| san_types.iter().any(|san_type| san_type == kind));
+        let shown = |len: usize| {
+            if options.full_sans {
+                len
+            } else {
+                len.min(SAN_PREVIEW)
+            }
+        };
+        let sans = sans
+            .iter()
+            .map(|(_kind, name)| name.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            strings(record.get("san")),
+            sans[..shown(sans.len())],
+            "seed {}",
+            seed
+        );
+        let other = other
+            .iter()
+            .map(|(kind, name)| (kind.to_string(), name.clone()))
+            .collect::<Vec<(String, String)>>();
+        let san_other = record
+            .get("san_other")
+            .and_then(|other| other.as_list().ok())
+            .unwrap_or_default()
+            .iter()
+            .map(|other| {
+                let column = |column| {
+                    other
+                        .get_data_by_key(column)
+                        .and_then(|value| value.into_string().ok())
+                        .unwrap_or_default()
+                };
+                (column("type"), column("value"))
+            })
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(san_other, other[..shown(other.len())], "seed {}", seed);
+        assert_eq!(
+            record
+                .get("san_count")
+                .and_then(|count| count.as_int().ok()),
+            Some(expected.names.len() as i64),
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            record.get("san_truncated").is_some(),
+            sans.len() > shown(sans.len()),
+            "seed {}",
+            seed
+        );
+        others += expected
+            .names
+            .iter()
+            .filter(|(kind, _name)| !matches!(*kind, "dns" | "ip"))
+            .count();
+    }
+    assert!(others > CASES as usize);
+}
+
+#[test]
+fn generated_bundles_keep_their_order() {
+    let span = Span::test_data();
+    let mut random = Random::new(CASES);
+    let ders = (0..50)
+        .map(|_| generate_certificate(&mut random).0)
+        .collect::<Vec<Vec<u8>>>();
+    let bundle = ders
+        .iter()
+        .map(|der| to_pem("CERTIFICATE", der))
+        .collect::<String>();
+    let values = get_pem_values(&bundle, &RecordOptions::default(), span).expect("records");
+    let hashes = values
+        .iter()
+        .map(|value| value.get_data_by_key("sha256"))
+        .collect::<Vec<Option<Value>>>();
+    let expected = ders
+        .iter()
+        .map(|der| Some(get_sha256(der, span)))
+        .collect::<Vec<Option<Value>>>();
+    assert_eq!(hashes, expected);
+}
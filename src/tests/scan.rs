@@ -0,0 +1,30 @@
+use std::path::{Path, PathBuf};
+
+use crate::{core::config::Binding, scan::expand_binding};
+
+#[test]
+fn haproxy_directories_skip_the_extra_files() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures/haproxy");
+    let binding = Binding {
+        format: "haproxy",
+        config: PathBuf::from("/etc/haproxy/haproxy.cfg"),
+        line: 12,
+        server: None,
+        cert: directory.clone(),
+        key: None,
+    };
+    let bindings = expand_binding(binding)
+        .into_iter()
+        .map(|binding| (binding.cert, binding.key))
+        .collect::<Vec<(PathBuf, Option<PathBuf>)>>();
+    assert_eq!(
+        bindings,
+        [
+            (directory.join("other.pem"), None),
+            (
+                directory.join("site.pem"),
+                Some(directory.join("site.pem.key"))
+            ),
+        ]
+    );
+}
@@ -0,0 +1,30 @@
+use crate::core::spiffe::parse_spiffe_id;
+
+#[test]
+fn valid_spiffe_ids_are_split() {
+    assert_eq!(
+        parse_spiffe_id("spiffe://example.org/ns/prod/sa/web"),
+        Ok(("example.org", "/ns/prod/sa/web"))
+    );
+    assert_eq!(
+        parse_spiffe_id("spiffe://example.org"),
+        Ok(("example.org", ""))
+    );
+}
+
+#[test]
+fn invalid_spiffe_ids_are_rejected() {
+    for id in [
+        "https://example.org/web",
+        "spiffe:///web",
+        "spiffe://Example.org/web",
+        "spiffe://example.org:8443/web",
+        "spiffe://user@example.org/web",
+        "spiffe://example.org/web/",
+        "spiffe://example.org//web",
+        "spiffe://example.org/../web",
+        "spiffe://example.org/web?query",
+    ] {
+        assert!(parse_spiffe_id(id).is_err(), "{} should be rejected", id);
+    }
+}
@@ -0,0 +1,8 @@
+//! src/core.rs as a crate of its own, its modules refer to each other through crate::core
+
+#[path = "../src"]
+mod plugin {
+    pub mod core;
+}
+
+pub use plugin::core;